@@ -0,0 +1,389 @@
+use crate::apu::noise::NoiseChannel;
+use crate::apu::square::SquareChannel;
+use crate::apu::wave::WaveChannel;
+use crate::clock::{Clocked, CLOCK_SPEED};
+use crate::interrupts::Interrupts;
+use crate::save_state::{Snapshot, StateReader, StateWriter};
+
+mod noise;
+mod square;
+mod units;
+mod wave;
+
+/// Rate of the PCM samples produced by the APU, in frames per second.
+pub(crate) const SAMPLE_RATE: u32 = 44_100;
+
+/// Number of output channels of the PCM samples (left and right).
+pub(crate) const OUTPUT_CHANNELS: u16 = 2;
+
+/// The frame sequencer runs at 512 Hz.
+const FRAME_SEQUENCER_PERIOD: u16 = 8192;
+
+/// Charge factor of the high-pass filter removing the DC offset of the DACs, per sample.
+const HIGH_PASS_CHARGE: f32 = 0.996;
+
+/// Bits forced to 1 when reading back NR10-NR52 (0xFF10-0xFF26).
+const READ_MASKS: [u8; 0x17] = [
+    0x80, 0x3F, 0x00, 0xFF, 0xBF, // NR10-NR14
+    0xFF, 0x3F, 0x00, 0xFF, 0xBF, // unused, NR21-NR24
+    0x7F, 0xFF, 0x9F, 0xFF, 0xBF, // NR30-NR34
+    0xFF, 0xFF, 0x00, 0x00, 0xBF, // unused, NR41-NR44
+    0x00, 0x00, 0x70, // NR50-NR52
+];
+
+/// Audio Processing Unit.
+///
+/// Emulates the two square channels (the first one with a frequency sweep), the wave channel and
+/// the noise channel, the 512 Hz frame sequencer clocking their length counters, envelopes and
+/// sweep, and mixes them through NR51 panning and NR50 master volume into signed 16-bit stereo
+/// samples at `SAMPLE_RATE`.
+pub(crate) struct APU {
+    registers: [u8; 0x17],
+    channel1: SquareChannel,
+    channel2: SquareChannel,
+    channel3: WaveChannel,
+    channel4: NoiseChannel,
+    powered: bool,
+    frame_sequencer_step: u8,
+    frame_sequencer_timer: u16,
+    /// Sum of the mixed output since the last sample, averaged when the sample is produced.
+    accumulator: (f32, f32),
+    accumulated_cycles: u32,
+    /// Charge of the capacitors of the (left, right) high-pass filters.
+    capacitors: (f32, f32),
+    /// Fractional position between two samples, in units of `1 / CLOCK_SPEED` seconds.
+    sample_timer: u32,
+    samples: Option<Vec<i16>>,
+}
+
+impl APU {
+    pub(crate) const NR10_ADDRESS: u16 = 0xFF10;
+    pub(crate) const NR52_ADDRESS: u16 = 0xFF26;
+    pub(crate) const WAVE_RAM_START: u16 = 0xFF30;
+    pub(crate) const WAVE_RAM_END: u16 = 0xFF3F;
+
+    const NR50: usize = 0x14;
+    const NR51: usize = 0x15;
+
+    pub(crate) fn new() -> Self {
+        APU {
+            registers: [0; 0x17],
+            channel1: SquareChannel::new(true),
+            channel2: SquareChannel::new(false),
+            channel3: WaveChannel::new(),
+            channel4: NoiseChannel::new(),
+            powered: false,
+            frame_sequencer_step: 0,
+            frame_sequencer_timer: FRAME_SEQUENCER_PERIOD,
+            accumulator: (0.0, 0.0),
+            accumulated_cycles: 0,
+            capacitors: (0.0, 0.0),
+            sample_timer: 0,
+            samples: None,
+        }
+    }
+
+    /// Starts producing PCM samples, to be retrieved with `take_samples`.
+    pub(crate) fn enable_output(&mut self) {
+        self.samples = Some(Vec::new());
+    }
+
+    /// Returns the interleaved stereo samples produced since the last call.
+    pub(crate) fn take_samples(&mut self) -> Vec<i16> {
+        self.samples.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    pub(crate) fn read_byte(&self, addr: u16) -> u8 {
+        match addr {
+            Self::NR52_ADDRESS => {
+                let channels = [self.channel1.enabled, self.channel2.enabled, self.channel3.enabled, self.channel4.enabled];
+                let status = channels.iter().enumerate()
+                    .fold(0, |status, (index, &enabled)| status | ((enabled as u8) << index));
+                READ_MASKS[0x16] | ((self.powered as u8) << 7) | status
+            }
+            Self::NR10_ADDRESS..=Self::NR52_ADDRESS => {
+                let index = (addr - Self::NR10_ADDRESS) as usize;
+                self.registers[index] | READ_MASKS[index]
+            }
+            Self::WAVE_RAM_START..=Self::WAVE_RAM_END => self.channel3.wave_ram[(addr - Self::WAVE_RAM_START) as usize],
+            _ => 0xFF,
+        }
+    }
+
+    pub(crate) fn write_byte(&mut self, addr: u16, value: u8) {
+        match addr {
+            Self::NR52_ADDRESS => self.write_power(value & 0b1000_0000 != 0),
+            Self::WAVE_RAM_START..=Self::WAVE_RAM_END => self.channel3.wave_ram[(addr - Self::WAVE_RAM_START) as usize] = value,
+            _ if !self.powered => (),
+            Self::NR10_ADDRESS..=Self::NR52_ADDRESS => {
+                let index = addr - Self::NR10_ADDRESS;
+                self.registers[index as usize] = value;
+                match index / 5 {
+                    0 => self.channel1.write(index % 5, value),
+                    1 => self.channel2.write(index % 5, value),
+                    2 => self.channel3.write(index % 5, value),
+                    3 => self.channel4.write(index % 5, value),
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn write_power(&mut self, powered: bool) {
+        if self.powered && !powered {
+            // Powering the APU off clears every register but wave RAM.
+            let wave_ram = self.channel3.wave_ram;
+            *self = APU {
+                samples: self.samples.take(),
+                ..APU::new()
+            };
+            self.channel3.wave_ram = wave_ram;
+        } else if !self.powered && powered {
+            self.frame_sequencer_step = 0;
+            self.frame_sequencer_timer = FRAME_SEQUENCER_PERIOD;
+        }
+        self.powered = powered;
+    }
+
+    fn tick_channels(&mut self) {
+        self.channel1.tick();
+        self.channel2.tick();
+        self.channel3.tick();
+        self.channel4.tick();
+
+        self.frame_sequencer_timer -= 1;
+        if self.frame_sequencer_timer == 0 {
+            self.frame_sequencer_timer = FRAME_SEQUENCER_PERIOD;
+            self.clock_frame_sequencer();
+        }
+    }
+
+    /// Steps the frame sequencer: length counters are clocked at 256 Hz, the sweep at 128 Hz
+    /// and the envelopes at 64 Hz.
+    fn clock_frame_sequencer(&mut self) {
+        if self.frame_sequencer_step.is_multiple_of(2) {
+            self.channel1.clock_length();
+            self.channel2.clock_length();
+            self.channel3.clock_length();
+            self.channel4.clock_length();
+        }
+        if self.frame_sequencer_step == 2 || self.frame_sequencer_step == 6 {
+            self.channel1.clock_sweep();
+        }
+        if self.frame_sequencer_step == 7 {
+            self.channel1.clock_envelope();
+            self.channel2.clock_envelope();
+            self.channel4.clock_envelope();
+        }
+        self.frame_sequencer_step = (self.frame_sequencer_step + 1) % 8;
+    }
+
+    fn tick_output(&mut self) {
+        let (left, right) = self.mix();
+        self.accumulator.0 += left;
+        self.accumulator.1 += right;
+        self.accumulated_cycles += 1;
+
+        self.sample_timer += SAMPLE_RATE;
+        if self.sample_timer < CLOCK_SPEED {
+            return;
+        }
+        self.sample_timer -= CLOCK_SPEED;
+        let cycles = self.accumulated_cycles as f32;
+        let (left, right) = (self.accumulator.0 / cycles, self.accumulator.1 / cycles);
+        self.accumulator = (0.0, 0.0);
+        self.accumulated_cycles = 0;
+        let left = Self::high_pass(&mut self.capacitors.0, left);
+        let right = Self::high_pass(&mut self.capacitors.1, right);
+        if let Some(samples) = &mut self.samples {
+            samples.push((left * i16::MAX as f32) as i16);
+            samples.push((right * i16::MAX as f32) as i16);
+        }
+    }
+
+    fn high_pass(capacitor: &mut f32, input: f32) -> f32 {
+        let output = input - *capacitor;
+        *capacitor = input - output * HIGH_PASS_CHARGE;
+        output
+    }
+
+    /// Mixes the channels into a (left, right) pair of values between -1.0 and 1.0.
+    fn mix(&self) -> (f32, f32) {
+        if !self.powered {
+            return (0.0, 0.0);
+        }
+        let outputs = [
+            (self.channel1.dac_enabled(), self.channel1.output()),
+            (self.channel2.dac_enabled(), self.channel2.output()),
+            (self.channel3.dac_enabled(), self.channel3.output()),
+            (self.channel4.dac_enabled(), self.channel4.output()),
+        ];
+        let panning = self.registers[Self::NR51];
+        let (mut left, mut right) = (0.0, 0.0);
+        for (index, &(dac_enabled, output)) in outputs.iter().enumerate() {
+            if !dac_enabled {
+                continue;
+            }
+            // The DACs map digital outputs 0 to 15 onto analog values 1.0 to -1.0.
+            let analog = 1.0 - output as f32 / 7.5;
+            if panning & (1 << (index + 4)) != 0 {
+                left += analog;
+            }
+            if panning & (1 << index) != 0 {
+                right += analog;
+            }
+        }
+        let volume = self.registers[Self::NR50];
+        let left_volume = (((volume >> 4) & 0b111) + 1) as f32 / 8.0;
+        let right_volume = ((volume & 0b111) + 1) as f32 / 8.0;
+        (left / 4.0 * left_volume, right / 4.0 * right_volume)
+    }
+}
+
+impl Clocked for APU {
+    fn tick(&mut self, cycles: u8, _interrupts: &mut Interrupts) {
+        for _ in 0..cycles {
+            if self.powered {
+                self.tick_channels();
+            }
+            if self.samples.is_some() {
+                self.tick_output();
+            }
+        }
+    }
+}
+
+/// Whether samples are produced is not part of the state, nor are the samples not taken yet.
+impl Snapshot for APU {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_bytes(&self.registers);
+        self.channel1.save_state(state);
+        self.channel2.save_state(state);
+        self.channel3.save_state(state);
+        self.channel4.save_state(state);
+        state.write_bool(self.powered);
+        state.write_u8(self.frame_sequencer_step);
+        state.write_u16(self.frame_sequencer_timer);
+        for value in [self.accumulator.0, self.accumulator.1, self.capacitors.0, self.capacitors.1] {
+            state.write_f32(value);
+        }
+        state.write_u32(self.accumulated_cycles);
+        state.write_u32(self.sample_timer);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        state.read_bytes(&mut self.registers)?;
+        self.channel1.load_state(state)?;
+        self.channel2.load_state(state)?;
+        self.channel3.load_state(state)?;
+        self.channel4.load_state(state)?;
+        self.powered = state.read_bool()?;
+        self.frame_sequencer_step = state.read_u8()? % 8;
+        self.frame_sequencer_timer = state.read_u16()?;
+        self.accumulator = (state.read_f32()?, state.read_f32()?);
+        self.capacitors = (state.read_f32()?, state.read_f32()?);
+        self.accumulated_cycles = state.read_u32()?;
+        self.sample_timer = state.read_u32()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn powered() -> APU {
+        let mut apu = APU::new();
+        apu.write_byte(APU::NR52_ADDRESS, 0x80);
+        apu
+    }
+
+    fn tick(apu: &mut APU, cycles: u32) {
+        let mut interrupts = Interrupts::new();
+        for _ in 0..cycles / 4 {
+            apu.tick(4, &mut interrupts);
+        }
+    }
+
+    /// Powered APU where the next sweep clock disables channel 1, the next envelope clock turns
+    /// up channel 2 and the next length clock disables channel 4.
+    fn apu_about_to_clock_its_units() -> APU {
+        let mut apu = powered();
+        apu.write_byte(0xFF10, 0x11); // NR10: period 1, increase, shift 1
+        apu.write_byte(0xFF12, 0xF0); // NR12: volume 15
+        apu.write_byte(0xFF13, 0x50); // NR13
+        apu.write_byte(0xFF14, 0x85); // NR14: trigger with frequency 0x550
+        apu.write_byte(0xFF16, 0x80); // NR21: duty 50%
+        apu.write_byte(0xFF17, 0x09); // NR22: volume 0, increase, period 1
+        apu.write_byte(0xFF19, 0x80); // NR24: trigger
+        apu.write_byte(0xFF20, 0x3F); // NR41: length 1
+        apu.write_byte(0xFF21, 0xF0); // NR42: volume 15
+        apu.write_byte(0xFF23, 0xC0); // NR44: trigger with length enabled
+        apu
+    }
+
+    #[test]
+    fn frame_sequencer_clocks_length_sweep_and_envelope_on_their_steps() {
+        for step in 0..8 {
+            let mut apu = apu_about_to_clock_its_units();
+            apu.frame_sequencer_step = step;
+            apu.clock_frame_sequencer();
+            assert_eq!(!apu.channel4.enabled, step % 2 == 0, "length clocked on step {}", step);
+            assert_eq!(!apu.channel1.enabled, step == 2 || step == 6, "sweep clocked on step {}", step);
+            assert_eq!(apu.channel2.output() == 1, step == 7, "envelope clocked on step {}", step);
+            assert_eq!(apu.frame_sequencer_step, (step + 1) % 8);
+        }
+    }
+
+    #[test]
+    fn frame_sequencer_steps_every_8192_cycles() {
+        let mut apu = powered();
+        tick(&mut apu, 8188);
+        assert_eq!(apu.frame_sequencer_step, 0);
+        tick(&mut apu, 4);
+        assert_eq!(apu.frame_sequencer_step, 1);
+        tick(&mut apu, 7 * 8192);
+        assert_eq!(apu.frame_sequencer_step, 0);
+    }
+
+    #[test]
+    fn length_counter_expiry_disables_the_channel() {
+        let mut apu = powered();
+        apu.write_byte(0xFF16, 0x3E); // NR21: length 2
+        apu.write_byte(0xFF17, 0xF0); // NR22: volume 15
+        apu.write_byte(0xFF19, 0xC0); // NR24: trigger with length enabled
+        assert_eq!(apu.read_byte(APU::NR52_ADDRESS) & 0x0F, 0b0010);
+
+        // Lengths are clocked on the steps 0 and 2, after 8192 and 3 * 8192 cycles.
+        tick(&mut apu, 2 * 8192);
+        assert_eq!(apu.read_byte(APU::NR52_ADDRESS) & 0x0F, 0b0010);
+        tick(&mut apu, 8192);
+        assert_eq!(apu.read_byte(APU::NR52_ADDRESS) & 0x0F, 0b0000);
+    }
+
+    #[test]
+    fn nr52_reflects_power_and_channel_status() {
+        let mut apu = APU::new();
+        assert_eq!(apu.read_byte(APU::NR52_ADDRESS), 0x70);
+
+        apu.write_byte(APU::NR52_ADDRESS, 0x80);
+        apu.write_byte(0xFF12, 0xF0); // NR12: volume 15
+        apu.write_byte(0xFF14, 0x80); // NR14: trigger
+        apu.write_byte(0xFF1A, 0x80); // NR30: DAC on
+        apu.write_byte(0xFF1E, 0x80); // NR34: trigger
+        assert_eq!(apu.read_byte(APU::NR52_ADDRESS), 0xF5);
+
+        // Turning the DAC off disables the channel.
+        apu.write_byte(0xFF12, 0x00);
+        assert_eq!(apu.read_byte(APU::NR52_ADDRESS), 0xF4);
+
+        // Channel status bits are read-only.
+        apu.write_byte(APU::NR52_ADDRESS, 0x8F);
+        assert_eq!(apu.read_byte(APU::NR52_ADDRESS), 0xF4);
+
+        apu.write_byte(APU::NR52_ADDRESS, 0x00);
+        assert_eq!(apu.read_byte(APU::NR52_ADDRESS), 0x70);
+        assert_eq!(apu.read_byte(0xFF12), 0x00);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::apu::units::{Envelope, LengthCounter};
+use crate::save_state::{Snapshot, StateReader, StateWriter};
+
+const DIVISORS: [u16; 8] = [8, 16, 32, 48, 64, 80, 96, 112];
+
+/// Noise channel (channel 4), driven by a 15-bit linear feedback shift register.
+pub(super) struct NoiseChannel {
+    length: LengthCounter,
+    envelope: Envelope,
+    clock_shift: u8,
+    width_mode: bool,
+    divisor_code: u8,
+    timer: u32,
+    lfsr: u16,
+    pub(super) enabled: bool,
+}
+
+impl NoiseChannel {
+    pub(super) fn new() -> Self {
+        NoiseChannel {
+            length: LengthCounter::new(64),
+            envelope: Envelope::new(),
+            clock_shift: 0,
+            width_mode: false,
+            divisor_code: 0,
+            timer: 0,
+            lfsr: 0x7FFF,
+            enabled: false,
+        }
+    }
+
+    /// Handles a write to the register `NR4<register>`.
+    pub(super) fn write(&mut self, register: u16, value: u8) {
+        match register {
+            1 => self.length.load((value & 0b0011_1111) as u16),
+            2 => {
+                self.envelope.write(value);
+                if !self.envelope.dac_enabled() {
+                    self.enabled = false;
+                }
+            }
+            3 => {
+                self.clock_shift = value >> 4;
+                self.width_mode = value & 0b0000_1000 != 0;
+                self.divisor_code = value & 0b0000_0111;
+            }
+            4 => {
+                self.length.enabled = value & 0b0100_0000 != 0;
+                if value & 0b1000_0000 != 0 {
+                    self.trigger();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn trigger(&mut self) {
+        self.enabled = self.envelope.dac_enabled();
+        self.length.trigger();
+        self.timer = self.period();
+        self.envelope.trigger();
+        self.lfsr = 0x7FFF;
+    }
+
+    fn period(&self) -> u32 {
+        (DIVISORS[self.divisor_code as usize] as u32) << self.clock_shift
+    }
+
+    pub(super) fn tick(&mut self) {
+        self.timer = self.timer.saturating_sub(1);
+        if self.timer == 0 {
+            self.timer = self.period();
+            let feedback = (self.lfsr ^ (self.lfsr >> 1)) & 1;
+            self.lfsr = (self.lfsr >> 1) | (feedback << 14);
+            if self.width_mode {
+                self.lfsr = (self.lfsr & !(1 << 6)) | (feedback << 6);
+            }
+        }
+    }
+
+    pub(super) fn clock_length(&mut self) {
+        if self.length.clock() {
+            self.enabled = false;
+        }
+    }
+
+    pub(super) fn clock_envelope(&mut self) {
+        self.envelope.clock();
+    }
+
+    pub(super) fn dac_enabled(&self) -> bool {
+        self.envelope.dac_enabled()
+    }
+
+    /// Digital output of the channel, between 0 and 15.
+    pub(super) fn output(&self) -> u8 {
+        if !self.enabled || self.lfsr & 1 != 0 {
+            return 0;
+        }
+        self.envelope.volume
+    }
+}
+
+impl Snapshot for NoiseChannel {
+    fn save_state(&self, state: &mut StateWriter) {
+        self.length.save_state(state);
+        self.envelope.save_state(state);
+        state.write_u8(self.clock_shift);
+        state.write_bool(self.width_mode);
+        state.write_u8(self.divisor_code);
+        state.write_u32(self.timer);
+        state.write_u16(self.lfsr);
+        state.write_bool(self.enabled);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.length.load_state(state)?;
+        self.envelope.load_state(state)?;
+        self.clock_shift = state.read_u8()?;
+        self.width_mode = state.read_bool()?;
+        self.divisor_code = state.read_u8()? & 0b111;
+        self.timer = state.read_u32()?;
+        self.lfsr = state.read_u16()?;
+        self.enabled = state.read_bool()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Noise channel shifting its LFSR every 8 T-cycles, in 7-bit mode if `width_mode`.
+    fn triggered(width_mode: bool) -> NoiseChannel {
+        let mut channel = NoiseChannel::new();
+        channel.write(2, 0xF0);
+        channel.write(3, if width_mode { 0x08 } else { 0x00 });
+        channel.write(4, 0x80);
+        channel
+    }
+
+    fn shift(channel: &mut NoiseChannel) {
+        for _ in 0..8 {
+            channel.tick();
+        }
+    }
+
+    #[test]
+    fn lfsr_feeds_back_into_bit_6_in_7_bit_mode() {
+        let mut channel = triggered(true);
+        let values: Vec<u16> = (0..4).map(|_| {
+            shift(&mut channel);
+            channel.lfsr
+        }).collect();
+        assert_eq!(values, [0x3FBF, 0x1F9F, 0x0F8F, 0x0787]);
+    }
+
+    #[test]
+    fn lfsr_repeats_every_127_shifts_in_7_bit_mode() {
+        let mut channel = triggered(true);
+        let initial = channel.lfsr & 0x7F;
+        let mut period = 0;
+        loop {
+            shift(&mut channel);
+            period += 1;
+            if channel.lfsr & 0x7F == initial {
+                break;
+            }
+        }
+        assert_eq!(period, 127);
+    }
+
+    #[test]
+    fn lfsr_repeats_every_32767_shifts_in_15_bit_mode() {
+        let mut channel = triggered(false);
+        let mut period = 0;
+        loop {
+            shift(&mut channel);
+            period += 1;
+            if channel.lfsr == 0x7FFF {
+                break;
+            }
+        }
+        assert_eq!(period, 32767);
+    }
+}
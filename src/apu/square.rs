@@ -0,0 +1,278 @@
+use crate::apu::units::{Envelope, LengthCounter};
+use crate::save_state::{Snapshot, StateReader, StateWriter};
+
+const DUTY_PATTERNS: [[u8; 8]; 4] = [
+    [0, 0, 0, 0, 0, 0, 0, 1], // 12.5%
+    [1, 0, 0, 0, 0, 0, 0, 1], // 25%
+    [1, 0, 0, 0, 0, 1, 1, 1], // 50%
+    [0, 1, 1, 1, 1, 1, 1, 0], // 75%
+];
+
+/// Frequency sweep of channel 1, configured by NR10.
+struct Sweep {
+    period: u8,
+    negate: bool,
+    shift: u8,
+    timer: u8,
+    shadow_frequency: u16,
+    enabled: bool,
+}
+
+impl Sweep {
+    fn new() -> Self {
+        Sweep {
+            period: 0,
+            negate: false,
+            shift: 0,
+            timer: 0,
+            shadow_frequency: 0,
+            enabled: false,
+        }
+    }
+
+    fn write(&mut self, value: u8) {
+        self.period = (value >> 4) & 0b111;
+        self.negate = value & 0b0000_1000 != 0;
+        self.shift = value & 0b0000_0111;
+    }
+
+    fn reload_timer(&mut self) {
+        // A period of 0 is treated as 8 by the sweep timer.
+        self.timer = if self.period == 0 { 8 } else { self.period };
+    }
+
+    fn next_frequency(&self) -> u16 {
+        let delta = self.shadow_frequency >> self.shift;
+        if self.negate {
+            self.shadow_frequency - delta
+        } else {
+            self.shadow_frequency + delta
+        }
+    }
+}
+
+/// Square wave channel (channels 1 and 2, only the former having a sweep unit).
+pub(super) struct SquareChannel {
+    sweep: Option<Sweep>,
+    length: LengthCounter,
+    envelope: Envelope,
+    duty: u8,
+    duty_step: usize,
+    frequency: u16,
+    timer: u16,
+    pub(super) enabled: bool,
+}
+
+impl SquareChannel {
+    pub(super) fn new(with_sweep: bool) -> Self {
+        SquareChannel {
+            sweep: if with_sweep { Some(Sweep::new()) } else { None },
+            length: LengthCounter::new(64),
+            envelope: Envelope::new(),
+            duty: 0,
+            duty_step: 0,
+            frequency: 0,
+            timer: 0,
+            enabled: false,
+        }
+    }
+
+    /// Handles a write to the register `NRx<register>` of the channel.
+    pub(super) fn write(&mut self, register: u16, value: u8) {
+        match register {
+            0 => {
+                if let Some(sweep) = &mut self.sweep {
+                    sweep.write(value);
+                }
+            }
+            1 => {
+                self.duty = value >> 6;
+                self.length.load((value & 0b0011_1111) as u16);
+            }
+            2 => {
+                self.envelope.write(value);
+                if !self.envelope.dac_enabled() {
+                    self.enabled = false;
+                }
+            }
+            3 => self.frequency = (self.frequency & 0x700) | value as u16,
+            4 => {
+                self.frequency = (self.frequency & 0xFF) | (((value & 0b111) as u16) << 8);
+                self.length.enabled = value & 0b0100_0000 != 0;
+                if value & 0b1000_0000 != 0 {
+                    self.trigger();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn trigger(&mut self) {
+        self.enabled = self.envelope.dac_enabled();
+        self.length.trigger();
+        self.timer = self.period();
+        self.envelope.trigger();
+        if let Some(sweep) = &mut self.sweep {
+            sweep.shadow_frequency = self.frequency;
+            sweep.reload_timer();
+            sweep.enabled = sweep.period != 0 || sweep.shift != 0;
+            if sweep.shift != 0 && sweep.next_frequency() > 2047 {
+                self.enabled = false;
+            }
+        }
+    }
+
+    fn period(&self) -> u16 {
+        (2048 - self.frequency) * 4
+    }
+
+    pub(super) fn tick(&mut self) {
+        self.timer = self.timer.saturating_sub(1);
+        if self.timer == 0 {
+            self.timer = self.period();
+            self.duty_step = (self.duty_step + 1) % 8;
+        }
+    }
+
+    pub(super) fn clock_length(&mut self) {
+        if self.length.clock() {
+            self.enabled = false;
+        }
+    }
+
+    pub(super) fn clock_envelope(&mut self) {
+        self.envelope.clock();
+    }
+
+    pub(super) fn clock_sweep(&mut self) {
+        let Some(sweep) = &mut self.sweep else {
+            return;
+        };
+        sweep.timer = sweep.timer.saturating_sub(1);
+        if sweep.timer != 0 {
+            return;
+        }
+        sweep.reload_timer();
+        if !sweep.enabled || sweep.period == 0 {
+            return;
+        }
+        let frequency = sweep.next_frequency();
+        if frequency > 2047 {
+            self.enabled = false;
+        } else if sweep.shift != 0 {
+            sweep.shadow_frequency = frequency;
+            self.frequency = frequency;
+            // The new frequency is checked for overflow again, without being applied.
+            if sweep.next_frequency() > 2047 {
+                self.enabled = false;
+            }
+        }
+    }
+
+    pub(super) fn dac_enabled(&self) -> bool {
+        self.envelope.dac_enabled()
+    }
+
+    /// Digital output of the channel, between 0 and 15.
+    pub(super) fn output(&self) -> u8 {
+        if !self.enabled {
+            return 0;
+        }
+        DUTY_PATTERNS[self.duty as usize][self.duty_step] * self.envelope.volume
+    }
+}
+
+impl Snapshot for Sweep {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.period);
+        state.write_bool(self.negate);
+        state.write_u8(self.shift);
+        state.write_u8(self.timer);
+        state.write_u16(self.shadow_frequency);
+        state.write_bool(self.enabled);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.period = state.read_u8()?;
+        self.negate = state.read_bool()?;
+        self.shift = state.read_u8()?;
+        self.timer = state.read_u8()?;
+        self.shadow_frequency = state.read_u16()?;
+        self.enabled = state.read_bool()?;
+        Ok(())
+    }
+}
+
+/// Whether the channel has a sweep unit is not saved, as it is fixed for each channel.
+impl Snapshot for SquareChannel {
+    fn save_state(&self, state: &mut StateWriter) {
+        if let Some(sweep) = &self.sweep {
+            sweep.save_state(state);
+        }
+        self.length.save_state(state);
+        self.envelope.save_state(state);
+        state.write_u8(self.duty);
+        state.write_u8(self.duty_step as u8);
+        state.write_u16(self.frequency);
+        state.write_u16(self.timer);
+        state.write_bool(self.enabled);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        if let Some(sweep) = &mut self.sweep {
+            sweep.load_state(state)?;
+        }
+        self.length.load_state(state)?;
+        self.envelope.load_state(state)?;
+        self.duty = state.read_u8()? & 0b11;
+        self.duty_step = (state.read_u8()? % 8) as usize;
+        self.frequency = state.read_u16()?;
+        self.timer = state.read_u16()?;
+        self.enabled = state.read_bool()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Channel 1 with volume 15 and the given NR10 sweep, triggered with `frequency`.
+    fn triggered(sweep: u8, frequency: u16) -> SquareChannel {
+        let mut channel = SquareChannel::new(true);
+        channel.write(0, sweep);
+        channel.write(2, 0xF0);
+        channel.write(3, frequency as u8);
+        channel.write(4, 0x80 | (frequency >> 8) as u8);
+        channel
+    }
+
+    #[test]
+    fn sweep_overflow_on_trigger_disables_channel_1() {
+        // 0x700 + 0x700 / 2 overflows the 11-bit frequency.
+        assert!(!triggered(0x11, 0x700).enabled);
+        // Without shift, the overflow check is skipped on trigger.
+        assert!(triggered(0x10, 0x700).enabled);
+        assert!(triggered(0x11, 0x500).enabled);
+    }
+
+    #[test]
+    fn sweep_overflow_when_clocked_disables_channel_1() {
+        // 0x550 + 0x2A8 = 0x7F8 is applied, the next step to 0xBF4 would overflow.
+        let mut channel = triggered(0x11, 0x550);
+        assert!(channel.enabled);
+        channel.clock_sweep();
+        assert_eq!(channel.frequency, 0x7F8);
+        assert!(!channel.enabled);
+    }
+
+    #[test]
+    fn decreasing_sweep_never_overflows() {
+        let mut channel = triggered(0x19, 0x700);
+        for _ in 0..8 {
+            channel.clock_sweep();
+        }
+        assert!(channel.enabled);
+        assert!(channel.frequency < 0x700);
+    }
+}
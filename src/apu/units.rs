@@ -0,0 +1,123 @@
+use crate::save_state::{Snapshot, StateReader, StateWriter};
+
+/// Length counter silencing a channel once it reaches zero, when enabled.
+pub(super) struct LengthCounter {
+    max: u16,
+    counter: u16,
+    pub(super) enabled: bool,
+}
+
+impl LengthCounter {
+    pub(super) fn new(max: u16) -> Self {
+        LengthCounter {
+            max,
+            counter: 0,
+            enabled: false,
+        }
+    }
+
+    /// Loads the counter from the length data written to NRx1.
+    pub(super) fn load(&mut self, length: u16) {
+        self.counter = self.max - length;
+    }
+
+    pub(super) fn trigger(&mut self) {
+        if self.counter == 0 {
+            self.counter = self.max;
+        }
+    }
+
+    /// Clocked at 256 Hz by the frame sequencer, returns true when the channel must be disabled.
+    pub(super) fn clock(&mut self) -> bool {
+        if self.enabled && self.counter > 0 {
+            self.counter -= 1;
+            return self.counter == 0;
+        }
+        false
+    }
+}
+
+/// Volume envelope of the square and noise channels, configured by NRx2.
+pub(super) struct Envelope {
+    initial_volume: u8,
+    increase: bool,
+    period: u8,
+    timer: u8,
+    pub(super) volume: u8,
+}
+
+impl Envelope {
+    pub(super) fn new() -> Self {
+        Envelope {
+            initial_volume: 0,
+            increase: false,
+            period: 0,
+            timer: 0,
+            volume: 0,
+        }
+    }
+
+    pub(super) fn write(&mut self, value: u8) {
+        self.initial_volume = value >> 4;
+        self.increase = value & 0b0000_1000 != 0;
+        self.period = value & 0b0000_0111;
+    }
+
+    /// The DAC of a channel is powered as long as its NRx2 volume or direction bits are set.
+    pub(super) fn dac_enabled(&self) -> bool {
+        self.initial_volume != 0 || self.increase
+    }
+
+    pub(super) fn trigger(&mut self) {
+        self.volume = self.initial_volume;
+        self.timer = self.period;
+    }
+
+    /// Clocked at 64 Hz by the frame sequencer.
+    pub(super) fn clock(&mut self) {
+        if self.period == 0 {
+            return;
+        }
+        self.timer = self.timer.saturating_sub(1);
+        if self.timer == 0 {
+            self.timer = self.period;
+            if self.increase && self.volume < 15 {
+                self.volume += 1;
+            } else if !self.increase && self.volume > 0 {
+                self.volume -= 1;
+            }
+        }
+    }
+}
+
+impl Snapshot for LengthCounter {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u16(self.counter);
+        state.write_bool(self.enabled);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.counter = state.read_u16()?;
+        self.enabled = state.read_bool()?;
+        Ok(())
+    }
+}
+
+impl Snapshot for Envelope {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.initial_volume);
+        state.write_bool(self.increase);
+        state.write_u8(self.period);
+        state.write_u8(self.timer);
+        state.write_u8(self.volume);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.initial_volume = state.read_u8()?;
+        self.increase = state.read_bool()?;
+        self.period = state.read_u8()?;
+        self.timer = state.read_u8()?;
+        self.volume = state.read_u8()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+use crate::apu::units::LengthCounter;
+use crate::save_state::{Snapshot, StateReader, StateWriter};
+
+/// Wave channel (channel 3), playing the 32 4-bit samples of wave RAM (0xFF30-0xFF3F).
+pub(super) struct WaveChannel {
+    pub(super) wave_ram: [u8; 16],
+    length: LengthCounter,
+    dac_enabled: bool,
+    volume_code: u8,
+    frequency: u16,
+    timer: u16,
+    position: usize,
+    pub(super) enabled: bool,
+}
+
+impl WaveChannel {
+    pub(super) fn new() -> Self {
+        WaveChannel {
+            wave_ram: [0; 16],
+            length: LengthCounter::new(256),
+            dac_enabled: false,
+            volume_code: 0,
+            frequency: 0,
+            timer: 0,
+            position: 0,
+            enabled: false,
+        }
+    }
+
+    /// Handles a write to the register `NR3<register>`.
+    pub(super) fn write(&mut self, register: u16, value: u8) {
+        match register {
+            0 => {
+                self.dac_enabled = value & 0b1000_0000 != 0;
+                if !self.dac_enabled {
+                    self.enabled = false;
+                }
+            }
+            1 => self.length.load(value as u16),
+            2 => self.volume_code = (value >> 5) & 0b11,
+            3 => self.frequency = (self.frequency & 0x700) | value as u16,
+            4 => {
+                self.frequency = (self.frequency & 0xFF) | (((value & 0b111) as u16) << 8);
+                self.length.enabled = value & 0b0100_0000 != 0;
+                if value & 0b1000_0000 != 0 {
+                    self.trigger();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn trigger(&mut self) {
+        self.enabled = self.dac_enabled;
+        self.length.trigger();
+        self.timer = self.period();
+        self.position = 0;
+    }
+
+    fn period(&self) -> u16 {
+        (2048 - self.frequency) * 2
+    }
+
+    pub(super) fn tick(&mut self) {
+        self.timer = self.timer.saturating_sub(1);
+        if self.timer == 0 {
+            self.timer = self.period();
+            self.position = (self.position + 1) % 32;
+        }
+    }
+
+    pub(super) fn clock_length(&mut self) {
+        if self.length.clock() {
+            self.enabled = false;
+        }
+    }
+
+    pub(super) fn dac_enabled(&self) -> bool {
+        self.dac_enabled
+    }
+
+    /// Digital output of the channel, between 0 and 15.
+    pub(super) fn output(&self) -> u8 {
+        if !self.enabled {
+            return 0;
+        }
+        let byte = self.wave_ram[self.position / 2];
+        let sample = if self.position.is_multiple_of(2) { byte >> 4 } else { byte & 0x0F };
+        match self.volume_code {
+            0 => 0,
+            code => sample >> (code - 1),
+        }
+    }
+}
+
+impl Snapshot for WaveChannel {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_bytes(&self.wave_ram);
+        self.length.save_state(state);
+        state.write_bool(self.dac_enabled);
+        state.write_u8(self.volume_code);
+        state.write_u16(self.frequency);
+        state.write_u16(self.timer);
+        state.write_u8(self.position as u8);
+        state.write_bool(self.enabled);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        state.read_bytes(&mut self.wave_ram)?;
+        self.length.load_state(state)?;
+        self.dac_enabled = state.read_bool()?;
+        self.volume_code = state.read_u8()? & 0b11;
+        self.frequency = state.read_u16()?;
+        self.timer = state.read_u16()?;
+        self.position = (state.read_u8()? % 32) as usize;
+        self.enabled = state.read_bool()?;
+        Ok(())
+    }
+}
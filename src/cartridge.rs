@@ -158,6 +158,8 @@ pub(crate) enum RamSize {
     Kb2 = 0x01,
     Kb8 = 0x02,
     Kb32 = 0x03,
+    Kb128 = 0x04,
+    Kb64 = 0x05,
 }
 
 impl RamSize {
@@ -167,9 +169,22 @@ impl RamSize {
             0x01 => Some(RamSize::Kb2),
             0x02 => Some(RamSize::Kb8),
             0x03 => Some(RamSize::Kb32),
+            0x04 => Some(RamSize::Kb128),
+            0x05 => Some(RamSize::Kb64),
             _ => None,
         }
     }
+
+    pub(crate) fn bytes(&self) -> usize {
+        match self {
+            RamSize::None => 0,
+            RamSize::Kb2 => 0x800,
+            RamSize::Kb8 => 0x2000,
+            RamSize::Kb32 => 0x8000,
+            RamSize::Kb128 => 0x20000,
+            RamSize::Kb64 => 0x10000,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -0,0 +1,29 @@
+//! Emulated time.
+//!
+//! Every duration in the emulator is counted in T-cycles, the 4.194304 MHz ticks of the CPU
+//! clock. An M-cycle, the time taken by the CPU to access memory once, lasts 4 T-cycles: CPU
+//! instructions therefore always take a multiple of 4 T-cycles.
+
+use std::time::Duration;
+
+use crate::interrupts::Interrupts;
+
+/// Clock speed of the CPU, in T-cycles per second.
+pub(crate) const CLOCK_SPEED: u32 = 4_194_304;
+
+/// Number of T-cycles in an M-cycle.
+pub(crate) const M_CYCLE: u8 = 4;
+
+/// Number of T-cycles needed by the PPU to draw a frame.
+pub(crate) const CYCLES_PER_FRAME: u32 = 70_224;
+
+/// Converts a number of T-cycles to the time they take on hardware.
+pub(crate) fn cycles_to_time(cycles: u32) -> Duration {
+    Duration::from_nanos(cycles as u64 * 1_000_000_000 / CLOCK_SPEED as u64)
+}
+
+/// Component advancing alongside the CPU, ticked by the MMU after every CPU step with the
+/// T-cycles the step took.
+pub(crate) trait Clocked {
+    fn tick(&mut self, cycles: u8, interrupts: &mut Interrupts);
+}
@@ -0,0 +1,164 @@
+//! Disassembler rendering the instructions with their operands.
+//!
+//! Operands are rendered in hexadecimal: immediate values as `$12` or `$1234`, `a8` as the
+//! address `$FF12` it designates, and relative jumps as their absolute target. Addresses with a
+//! label in the symbols are rendered as the label.
+
+use crate::cpu::instructions::{Instruction, InstructionsMapsManager};
+use crate::mbc::ROM_BANK_SIZE;
+use crate::symbols::Symbols;
+
+/// Disassembled instruction.
+pub(crate) struct Line {
+    pub(crate) address: u16,
+    /// Label of the address of the instruction.
+    pub(crate) label: Option<String>,
+    pub(crate) bytes: Vec<u8>,
+    pub(crate) text: String,
+}
+
+pub(crate) struct Disassembler {
+    instructions_maps_manager: InstructionsMapsManager,
+    symbols: Option<Symbols>,
+}
+
+impl Disassembler {
+    pub(crate) fn new() -> Self {
+        Disassembler {
+            instructions_maps_manager: InstructionsMapsManager::new(),
+            symbols: None,
+        }
+    }
+
+    /// Renders the addresses with a label as the label.
+    pub(crate) fn with_symbols(self, symbols: Option<Symbols>) -> Self {
+        Disassembler { symbols, ..self }
+    }
+
+    pub(crate) fn symbols(&self) -> Option<&Symbols> {
+        self.symbols.as_ref()
+    }
+
+    /// Label of `address` when `rom_bank` is mapped at 0x4000-0x7FFF.
+    pub(crate) fn label(&self, address: u16, rom_bank: usize) -> Option<&str> {
+        self.symbols.as_ref()?.label(address, rom_bank)
+    }
+
+    /// Disassembles the instruction at `address`, reading memory with `read` while `rom_bank`
+    /// is mapped at 0x4000-0x7FFF.
+    pub(crate) fn disassemble(&self, address: u16, rom_bank: usize, read: impl Fn(u16) -> u8) -> Line {
+        let label = self.label(address, rom_bank).map(str::to_string);
+        let byte = read(address);
+        let instruction = match byte {
+            InstructionsMapsManager::PREFIX_CB => self.instructions_maps_manager.get_prefixed(read(address.wrapping_add(1))),
+            // Illegal opcodes are most likely data, rendered as such so that the listing reassembles.
+            _ if InstructionsMapsManager::is_illegal(byte) => None,
+            _ => self.instructions_maps_manager.get(byte),
+        };
+        let Some(Instruction { mnemonic, bytes: length, .. }) = instruction else {
+            return Line { address, label, bytes: vec![byte], text: format!("DB ${:02X}", byte) };
+        };
+        let bytes: Vec<u8> = (0..length as u16).map(|offset| read(address.wrapping_add(offset))).collect();
+        let text = if byte == InstructionsMapsManager::PREFIX_CB {
+            mnemonic.to_string()
+        } else {
+            self.render_operands(mnemonic, address, rom_bank, &bytes[1..])
+        };
+        Line { address, label, bytes, text }
+    }
+
+    /// Address operand, or its label.
+    fn render_address(&self, address: u16, rom_bank: usize) -> String {
+        match self.label(address, rom_bank) {
+            Some(label) => label.to_string(),
+            None => format!("${:04X}", address),
+        }
+    }
+
+    /// Replaces the operand placeholder of `mnemonic` with the `operands` following the opcode.
+    fn render_operands(&self, mnemonic: &str, address: u16, rom_bank: usize, operands: &[u8]) -> String {
+        match operands {
+            [low, high] => {
+                let word = u16::from_le_bytes([*low, *high]);
+                mnemonic.replace("d16", &format!("${:04X}", word)).replace("a16", &self.render_address(word, rom_bank))
+            }
+            [byte] if mnemonic.contains("a8") => mnemonic.replace("a8", &self.render_address(0xFF00 | *byte as u16, rom_bank)),
+            [byte] if mnemonic.starts_with("JR") => {
+                let target = address.wrapping_add(2).wrapping_add(*byte as i8 as u16);
+                mnemonic.replace("r8", &self.render_address(target, rom_bank))
+            }
+            [byte] => render_immediate(mnemonic, *byte),
+            _ => mnemonic.to_string(),
+        }
+    }
+}
+
+/// Replaces the `d8` or signed `r8` placeholder of `mnemonic` with `byte`.
+fn render_immediate(mnemonic: &str, byte: u8) -> String {
+    match byte {
+        _ if mnemonic.contains("r8") => {
+            let offset = byte as i8;
+            let sign = if offset < 0 { "-" } else { "+" };
+            let signed = format!("{}${:02X}", sign, offset.unsigned_abs());
+            // `SP+r8` carries its own sign.
+            mnemonic.replace("+r8", &signed).replace("r8", &signed)
+        }
+        _ => mnemonic.replace("d8", &format!("${:02X}", byte)),
+    }
+}
+
+/// Prints the disassembly of the `start..=end` address range of the given ROM banks, as mapped
+/// in memory: bank 0 at 0x0000-0x3FFF and the others at 0x4000-0x7FFF.
+///
+/// Addresses in 0x4000-0x7FFF are labelled as in the bank being disassembled, bank 1 for bank 0.
+pub(crate) fn dump(rom: &[u8], symbols: Option<Symbols>, first_bank: usize, last_bank: usize, start: u16, end: u16) -> Result<(), String> {
+    let bank_count = rom.len().div_ceil(ROM_BANK_SIZE);
+    if last_bank >= bank_count {
+        return Err(format!("Bank {:X} out of range, the last bank of the ROM is {:X}", last_bank, bank_count - 1));
+    }
+    let disassembler = Disassembler::new().with_symbols(symbols);
+    for bank in first_bank..=last_bank {
+        let window = if bank == 0 { 0x0000..=0x3FFF } else { 0x4000..=0x7FFF };
+        let (start, end) = (start.max(*window.start()), end.min(*window.end()));
+        // Operands past the end of the bank are read from the next one, as the ROM is contiguous.
+        let read = |address: u16| {
+            let offset = bank * ROM_BANK_SIZE + (address as usize - *window.start() as usize);
+            rom.get(offset).copied().unwrap_or(0xFF)
+        };
+        let mut address = start as u32;
+        while address <= end as u32 {
+            let line = disassembler.disassemble(address as u16, bank.max(1), read);
+            if let Some(label) = &line.label {
+                println!("{}:", label);
+            }
+            let bytes: Vec<String> = line.bytes.iter().map(|byte| format!("{:02X}", byte)).collect();
+            println!("{:02X}:{:04X}  {:<8}  {}", bank, line.address, bytes.join(" "), line.text);
+            address += line.bytes.len() as u32;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn disassemble(bytes: &[u8]) -> String {
+        let line = Disassembler::new().disassemble(0x0150, 1, |address| bytes.get(address as usize - 0x0150).copied().unwrap_or(0));
+        line.text
+    }
+
+    #[test]
+    fn renders_operands() {
+        assert_eq!(disassemble(&[0xC3, 0x34, 0x12]), "JP $1234");
+        assert_eq!(disassemble(&[0xE0, 0x40]), "LDH ($FF40), A");
+        assert_eq!(disassemble(&[0x18, 0xFE]), "JR $0150");
+    }
+
+    #[test]
+    fn renders_illegal_opcodes_as_data() {
+        for opcode in [0xD3, 0xDB, 0xDD, 0xE3, 0xE4, 0xEB, 0xEC, 0xED, 0xF4, 0xFC, 0xFD] {
+            assert_eq!(disassemble(&[opcode]), format!("DB ${:02X}", opcode));
+        }
+    }
+}
@@ -1,13 +1,13 @@
-use crate::cpu::instructions::{Cycles, ExecutionResult, Instruction, InstructionsMap};
+use crate::cpu::instructions::{ExecutionResult, Instruction, InstructionsMap};
 use crate::cpu::registers::Flags;
 
 pub(super)
-fn instructions_map_arithmetic_logical_instructions(instructions_map: &mut InstructionsMap) -> () {
+fn instructions_map_arithmetic_logical_instructions(instructions_map: &mut InstructionsMap) {
     instructions_map_8_bit_arithmetic_logical_instructions(instructions_map);
     instructions_map_16_bit_arithmetic_logical_instructions(instructions_map);
 }
 
-fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut InstructionsMap) -> () {
+fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut InstructionsMap) {
     fn operation(flags: &mut Flags, operator: fn(&mut Flags)) { operator(flags) }
     fn unary_operation(data: u8, flags: &mut Flags, unary_operator: fn(u8, &mut Flags) -> u8) -> u8 { unary_operator(data, flags) }
     fn binary_operation(left: u8, right: u8, flags: &mut Flags, binary_operator: fn(u8, u8, &mut Flags) -> u8) -> u8 { binary_operator(left, right, flags) }
@@ -149,7 +149,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "INC B", |registers, _memory| {
                 registers.b = unary_operation(registers.b, &mut registers.f, inc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -158,7 +158,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "INC D", |registers, _memory| {
                 registers.d = unary_operation(registers.d, &mut registers.f, inc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -167,16 +167,16 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "INC H", |registers, _memory| {
                 registers.h = unary_operation(registers.h, &mut registers.f, inc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
     instructions_map.insert(
         0x34, Instruction::new(
             "INC (HL)", |registers, memory| {
-                memory.write_byte(registers.get_bc(), unary_operation(memory.read_byte(registers.get_bc()), &mut registers.f, inc_operator));
+                memory.write_byte(registers.get_hl(), unary_operation(memory.read_byte(registers.get_hl()), &mut registers.f, inc_operator));
                 ExecutionResult::default()
-            }, Cycles::new(12), 1,
+            }, 1,
         ),
     );
 
@@ -185,7 +185,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "INC C", |registers, _memory| {
                 registers.c = unary_operation(registers.c, &mut registers.f, inc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -194,7 +194,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "INC E", |registers, _memory| {
                 registers.e = unary_operation(registers.e, &mut registers.f, inc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -203,7 +203,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "INC L", |registers, _memory| {
                 registers.l = unary_operation(registers.l, &mut registers.f, inc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -212,7 +212,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "INC A", |registers, _memory| {
                 registers.a = unary_operation(registers.a, &mut registers.f, inc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -221,7 +221,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "DEC B", |registers, _memory| {
                 registers.b = unary_operation(registers.b, &mut registers.f, dec_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -230,25 +230,25 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "DEC D", |registers, _memory| {
                 registers.d = unary_operation(registers.d, &mut registers.f, dec_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
     instructions_map.insert(
         0x25, Instruction::new(
-            "DEC L", |registers, _memory| {
-                registers.l = unary_operation(registers.l, &mut registers.f, dec_operator);
+            "DEC H", |registers, _memory| {
+                registers.h = unary_operation(registers.h, &mut registers.f, dec_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
     instructions_map.insert(
         0x35, Instruction::new(
             "DEC (HL)", |registers, memory| {
-                memory.write_byte(registers.get_bc(), unary_operation(memory.read_byte(registers.get_bc()), &mut registers.f, inc_operator));
+                memory.write_byte(registers.get_hl(), unary_operation(memory.read_byte(registers.get_hl()), &mut registers.f, dec_operator));
                 ExecutionResult::default()
-            }, Cycles::new(12), 1,
+            }, 1,
         ),
     );
 
@@ -257,7 +257,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "DEC C", |registers, _memory| {
                 registers.c = unary_operation(registers.c, &mut registers.f, dec_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -266,7 +266,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "DEC E", |registers, _memory| {
                 registers.e = unary_operation(registers.e, &mut registers.f, dec_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -275,7 +275,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "DEC L", |registers, _memory| {
                 registers.l = unary_operation(registers.l, &mut registers.f, dec_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -284,7 +284,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "DEC A", |registers, _memory| {
                 registers.a = unary_operation(registers.a, &mut registers.f, dec_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -293,7 +293,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "DAA", |registers, _memory| {
                 registers.a = unary_operation(registers.a, &mut registers.f, daa_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -302,7 +302,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "SCF", |registers, _memory| {
                 operation(&mut registers.f, scf_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -311,7 +311,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "CPL", |registers, _memory| {
                 registers.a = unary_operation(registers.a, &mut registers.f, cpl_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -320,7 +320,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "CCF", |registers, _memory| {
                 operation(&mut registers.f, ccf_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -329,7 +329,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "ADD A, B", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.b, &mut registers.f, add_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -338,7 +338,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "ADD A, C", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.c, &mut registers.f, add_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -347,7 +347,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "ADD A, D", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.d, &mut registers.f, add_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -356,7 +356,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "ADD A, E", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.e, &mut registers.f, add_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -365,7 +365,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "ADD A, H", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.h, &mut registers.f, add_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -374,7 +374,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "ADD A, L", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.l, &mut registers.f, add_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -383,7 +383,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "ADD A, (HL)", |registers, memory| {
                 registers.a = binary_operation(registers.a, memory.read_byte(registers.get_hl()), &mut registers.f, add_operator);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -392,7 +392,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "ADD A, A", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.a, &mut registers.f, add_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -401,7 +401,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "ADC A, B", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.b, &mut registers.f, adc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -410,7 +410,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "ADC A, C", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.c, &mut registers.f, adc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -419,7 +419,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "ADC A, D", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.d, &mut registers.f, adc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -428,7 +428,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "ADC A, E", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.e, &mut registers.f, adc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -437,7 +437,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "ADC A, H", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.h, &mut registers.f, adc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -446,7 +446,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "ADC A, L", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.l, &mut registers.f, adc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -455,7 +455,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "ADC A, (HL)", |registers, memory| {
                 registers.a = binary_operation(registers.a, memory.read_byte(registers.get_hl()), &mut registers.f, adc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -464,7 +464,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "ADC A, A", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.a, &mut registers.f, adc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -473,7 +473,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "SUB A, B", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.b, &mut registers.f, sub_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -482,7 +482,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "SUB A, C", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.c, &mut registers.f, sub_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -491,7 +491,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "SUB A, D", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.d, &mut registers.f, sub_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -500,7 +500,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "SUB A, E", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.e, &mut registers.f, sub_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -509,7 +509,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "SUB A, H", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.h, &mut registers.f, sub_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -518,7 +518,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "SUB A, L", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.l, &mut registers.f, sub_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -527,7 +527,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "SUB A, (HL)", |registers, memory| {
                 registers.a = binary_operation(registers.a, memory.read_byte(registers.get_hl()), &mut registers.f, sub_operator);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -536,7 +536,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "SUB A, A", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.a, &mut registers.f, sub_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -545,7 +545,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "SBC A, B", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.b, &mut registers.f, sbc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -554,7 +554,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "SBC A, C", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.c, &mut registers.f, sbc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -563,7 +563,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "SBC A, D", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.d, &mut registers.f, sbc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -572,7 +572,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "SBC A, E", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.e, &mut registers.f, sbc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -581,7 +581,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "SBC A, H", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.h, &mut registers.f, sbc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -590,7 +590,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "SBC A, L", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.l, &mut registers.f, sbc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -599,7 +599,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "SBC A, (HL)", |registers, memory| {
                 registers.a = binary_operation(registers.a, memory.read_byte(registers.get_hl()), &mut registers.f, sbc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -608,7 +608,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "SBC A, A", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.a, &mut registers.f, sbc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -617,7 +617,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "AND A, B", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.b, &mut registers.f, and_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -626,7 +626,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "AND A, C", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.c, &mut registers.f, and_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -635,7 +635,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "AND A, D", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.d, &mut registers.f, and_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -644,7 +644,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "AND A, E", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.e, &mut registers.f, and_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -653,7 +653,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "AND A, H", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.h, &mut registers.f, and_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -662,7 +662,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "AND A, L", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.l, &mut registers.f, and_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -671,7 +671,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "AND A, (HL)", |registers, memory| {
                 registers.a = binary_operation(registers.a, memory.read_byte(registers.get_hl()), &mut registers.f, and_operator);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -680,7 +680,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "AND A, A", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.a, &mut registers.f, and_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -689,7 +689,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "XOR A, B", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.b, &mut registers.f, xor_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -698,7 +698,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "XOR A, C", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.c, &mut registers.f, xor_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -707,7 +707,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "XOR A, D", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.d, &mut registers.f, xor_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -716,7 +716,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "XOR A, E", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.e, &mut registers.f, xor_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -725,7 +725,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "XOR A, H", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.h, &mut registers.f, xor_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -734,7 +734,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "XOR A, L", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.l, &mut registers.f, xor_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -743,7 +743,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "XOR A, (HL)", |registers, memory| {
                 registers.a = binary_operation(registers.a, memory.read_byte(registers.get_hl()), &mut registers.f, xor_operator);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -752,7 +752,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "XOR A, A", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.a, &mut registers.f, xor_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -761,7 +761,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "OR A, B", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.b, &mut registers.f, or_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -770,7 +770,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "OR A, C", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.c, &mut registers.f, or_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -779,7 +779,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "OR A, D", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.d, &mut registers.f, or_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -788,7 +788,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "OR A, E", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.e, &mut registers.f, or_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -797,7 +797,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "OR A, H", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.h, &mut registers.f, or_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -806,7 +806,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "OR A, L", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.l, &mut registers.f, or_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -815,7 +815,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "OR A, (HL)", |registers, memory| {
                 registers.a = binary_operation(registers.a, memory.read_byte(registers.get_hl()), &mut registers.f, or_operator);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -824,7 +824,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "OR A, A", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.a, &mut registers.f, or_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -833,7 +833,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "CP A, B", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.b, &mut registers.f, cp_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -842,7 +842,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "CP A, C", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.c, &mut registers.f, cp_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -851,7 +851,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "CP A, D", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.d, &mut registers.f, cp_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -860,7 +860,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "CP A, E", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.e, &mut registers.f, cp_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -869,7 +869,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "CP A, H", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.h, &mut registers.f, cp_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -878,7 +878,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "CP A, L", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.l, &mut registers.f, cp_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -887,7 +887,7 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "CP A, (HL)", |registers, memory| {
                 registers.a = binary_operation(registers.a, memory.read_byte(registers.get_hl()), &mut registers.f, cp_operator);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -896,84 +896,84 @@ fn instructions_map_8_bit_arithmetic_logical_instructions(instructions_map: &mut
             "CP A, A", |registers, _memory| {
                 registers.a = binary_operation(registers.a, registers.a, &mut registers.f, cp_operator);
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
     instructions_map.insert(
         0xC6, Instruction::new(
             "ADD A, d8", |registers, memory| {
-                registers.a = binary_operation(registers.a, memory.read_byte(registers.pc + 1), &mut registers.f, add_operator);
+                registers.a = binary_operation(registers.a, memory.read_byte(registers.pc.wrapping_add(1)), &mut registers.f, add_operator);
                 ExecutionResult::default()
-            }, Cycles::new(8), 2,
+            }, 2,
         ),
     );
 
     instructions_map.insert(
         0xD6, Instruction::new(
             "SUB A, d8", |registers, memory| {
-                registers.a = binary_operation(registers.a, memory.read_byte(registers.pc + 1), &mut registers.f, sub_operator);
+                registers.a = binary_operation(registers.a, memory.read_byte(registers.pc.wrapping_add(1)), &mut registers.f, sub_operator);
                 ExecutionResult::default()
-            }, Cycles::new(8), 2,
+            }, 2,
         ),
     );
 
     instructions_map.insert(
         0xE6, Instruction::new(
             "AND A, d8", |registers, memory| {
-                registers.a = binary_operation(registers.a, memory.read_byte(registers.pc + 1), &mut registers.f, and_operator);
+                registers.a = binary_operation(registers.a, memory.read_byte(registers.pc.wrapping_add(1)), &mut registers.f, and_operator);
                 ExecutionResult::default()
-            }, Cycles::new(8), 2,
+            }, 2,
         ),
     );
 
     instructions_map.insert(
         0xF6, Instruction::new(
             "OR A, d8", |registers, memory| {
-                registers.a = binary_operation(registers.a, memory.read_byte(registers.pc + 1), &mut registers.f, or_operator);
+                registers.a = binary_operation(registers.a, memory.read_byte(registers.pc.wrapping_add(1)), &mut registers.f, or_operator);
                 ExecutionResult::default()
-            }, Cycles::new(8), 2,
+            }, 2,
         ),
     );
 
     instructions_map.insert(
         0xCE, Instruction::new(
             "ADC A, d8", |registers, memory| {
-                registers.a = binary_operation(registers.a, memory.read_byte(registers.pc + 1), &mut registers.f, adc_operator);
+                registers.a = binary_operation(registers.a, memory.read_byte(registers.pc.wrapping_add(1)), &mut registers.f, adc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(8), 2,
+            }, 2,
         ),
     );
 
     instructions_map.insert(
         0xDE, Instruction::new(
             "SBC A, d8", |registers, memory| {
-                registers.a = binary_operation(registers.a, memory.read_byte(registers.pc + 1), &mut registers.f, sbc_operator);
+                registers.a = binary_operation(registers.a, memory.read_byte(registers.pc.wrapping_add(1)), &mut registers.f, sbc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(8), 2,
+            }, 2,
         ),
     );
 
     instructions_map.insert(
         0xEE, Instruction::new(
             "XOR A, d8", |registers, memory| {
-                registers.a = binary_operation(registers.a, memory.read_byte(registers.pc + 1), &mut registers.f, xor_operator);
+                registers.a = binary_operation(registers.a, memory.read_byte(registers.pc.wrapping_add(1)), &mut registers.f, xor_operator);
                 ExecutionResult::default()
-            }, Cycles::new(8), 2,
+            }, 2,
         ),
     );
 
     instructions_map.insert(
         0xFE, Instruction::new(
             "CP A, d8", |registers, memory| {
-                registers.a = binary_operation(registers.a, memory.read_byte(registers.pc + 1), &mut registers.f, cp_operator);
+                registers.a = binary_operation(registers.a, memory.read_byte(registers.pc.wrapping_add(1)), &mut registers.f, cp_operator);
                 ExecutionResult::default()
-            }, Cycles::new(8), 2,
+            }, 2,
         ),
     );
 }
 
-fn instructions_map_16_bit_arithmetic_logical_instructions(instructions_map: &mut InstructionsMap) -> () {
+fn instructions_map_16_bit_arithmetic_logical_instructions(instructions_map: &mut InstructionsMap) {
     fn unary_operation(data: u16, unary_operator: fn(u16) -> u16) -> u16 { unary_operator(data) }
     fn binary_operation(left: u16, right: u16, flags: &mut Flags, binary_operator: fn(u16, u16, &mut Flags) -> u16) -> u16 { binary_operator(left, right, flags) }
 
@@ -992,39 +992,48 @@ fn instructions_map_16_bit_arithmetic_logical_instructions(instructions_map: &mu
         result
     }
 
+    fn add_sp_operator(sp: u16, r8: u8, flags: &mut Flags) -> u16 {
+        let r8 = r8 as i8 as u16;
+        flags.z = false;
+        flags.n = false;
+        flags.h = (sp & 0x0F) + (r8 & 0x0F) > 0x0F;
+        flags.c = (sp & 0xFF) + (r8 & 0xFF) > 0xFF;
+        sp.wrapping_add(r8)
+    }
+
     instructions_map.insert(
-        0x04, Instruction::new(
+        0x03, Instruction::new(
             "INC BC", |registers, _memory| {
                 registers.set_bc(unary_operation(registers.get_bc(), inc_operator));
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
     instructions_map.insert(
-        0x14, Instruction::new(
+        0x13, Instruction::new(
             "INC DE", |registers, _memory| {
                 registers.set_de(unary_operation(registers.get_de(), inc_operator));
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
     instructions_map.insert(
-        0x24, Instruction::new(
+        0x23, Instruction::new(
             "INC HL", |registers, _memory| {
                 registers.set_hl(unary_operation(registers.get_hl(), inc_operator));
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
     instructions_map.insert(
-        0x34, Instruction::new(
+        0x33, Instruction::new(
             "INC SP", |registers, _memory| {
                 registers.sp = unary_operation(registers.sp, inc_operator);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -1034,7 +1043,7 @@ fn instructions_map_16_bit_arithmetic_logical_instructions(instructions_map: &mu
                 let hl = binary_operation(registers.get_hl(), registers.get_bc(), &mut registers.f, add_operator);
                 registers.set_hl(hl);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -1044,7 +1053,7 @@ fn instructions_map_16_bit_arithmetic_logical_instructions(instructions_map: &mu
                 let hl = binary_operation(registers.get_hl(), registers.get_de(), &mut registers.f, add_operator);
                 registers.set_hl(hl);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -1054,7 +1063,7 @@ fn instructions_map_16_bit_arithmetic_logical_instructions(instructions_map: &mu
                 let hl = binary_operation(registers.get_hl(), registers.get_hl(), &mut registers.f, add_operator);
                 registers.set_hl(hl);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -1064,7 +1073,7 @@ fn instructions_map_16_bit_arithmetic_logical_instructions(instructions_map: &mu
                 let hl = binary_operation(registers.get_hl(), registers.sp, &mut registers.f, add_operator);
                 registers.set_hl(hl);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -1073,7 +1082,7 @@ fn instructions_map_16_bit_arithmetic_logical_instructions(instructions_map: &mu
             "DEC BC", |registers, _memory| {
                 registers.set_bc(unary_operation(registers.get_bc(), dec_operator));
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -1082,7 +1091,7 @@ fn instructions_map_16_bit_arithmetic_logical_instructions(instructions_map: &mu
             "DEC DE", |registers, _memory| {
                 registers.set_de(unary_operation(registers.get_de(), dec_operator));
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -1091,7 +1100,7 @@ fn instructions_map_16_bit_arithmetic_logical_instructions(instructions_map: &mu
             "DEC HL", |registers, _memory| {
                 registers.set_hl(unary_operation(registers.get_hl(), dec_operator));
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -1100,7 +1109,16 @@ fn instructions_map_16_bit_arithmetic_logical_instructions(instructions_map: &mu
             "DEC SP", |registers, _memory| {
                 registers.sp = unary_operation(registers.sp, dec_operator);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
-}
\ No newline at end of file
+
+    instructions_map.insert(
+        0xE8, Instruction::new(
+            "ADD SP, r8", |registers, memory| {
+                registers.sp = add_sp_operator(registers.sp, memory.read_byte(registers.pc.wrapping_add(1)), &mut registers.f);
+                ExecutionResult::default()
+            }, 2,
+        ),
+    );
+}
@@ -1,10 +1,13 @@
-use crate::cpu::instructions::{Cycles, ExecutionResult, Instruction, InstructionsMap};
+use crate::cpu::instructions::{ExecutionResult, Instruction, InstructionsMap};
 use crate::cpu::registers::CpuState;
 
-pub(super) fn instructions_map_control_commands(instructions_map: &mut InstructionsMap) -> () {
+/// Opcodes without an instruction, which hang the CPU until it is reset.
+pub(super) const ILLEGAL_OPCODES: [u8; 11] = [0xD3, 0xDB, 0xDD, 0xE3, 0xE4, 0xEB, 0xEC, 0xED, 0xF4, 0xFC, 0xFD];
+
+pub(super) fn instructions_map_control_commands(instructions_map: &mut InstructionsMap) {
     instructions_map.insert(
         0x00, Instruction::new(
-            "NOP", |_registers, _memory| { ExecutionResult::default() }, Cycles::new(1), 1,
+            "NOP", |_registers, _memory| { ExecutionResult::default() }, 1,
         ),
     );
 
@@ -13,40 +16,49 @@ pub(super) fn instructions_map_control_commands(instructions_map: &mut Instructi
             "STOP", |registers, _memory| {
                 registers.cpu_state = CpuState::Stopped;
                 ExecutionResult::default()
-            }, Cycles::new(2), 1,
+            }, 2,
         ),
     );
 
     instructions_map.insert(
         0x76, Instruction::new(
-            "HALT", |registers, _memory| {
-                registers.cpu_state = CpuState::Halted;
+            "HALT", |registers, memory| {
+                if !registers.interrupts_enabled && memory.interrupts.pending().is_some() {
+                    registers.halt_bug = true;
+                } else {
+                    registers.cpu_state = CpuState::Halted;
+                }
                 ExecutionResult::default()
-            }, Cycles::new(1), 1,
-        ),
-    );
-
-    instructions_map.insert(
-        0xCB, Instruction::new(
-            "PREFIX", |_registers, _memory| { ExecutionResult::default() }, Cycles::new(1), 1,
+            }, 1,
         ),
     );
 
     instructions_map.insert(
         0xF3, Instruction::new(
             "DI", |registers, _memory| {
-                registers.interrupts_enabled = false;
+                registers.disable_interrupts();
                 ExecutionResult::default()
-            }, Cycles::new(1), 1,
+            }, 1,
         ),
     );
 
     instructions_map.insert(
         0xFB, Instruction::new(
             "EI", |registers, _memory| {
-                registers.interrupts_enabled = true;
+                registers.schedule_interrupts_enable();
                 ExecutionResult::default()
-            }, Cycles::new(1), 1,
+            }, 1,
         ),
     );
-}
\ No newline at end of file
+
+    for opcode in ILLEGAL_OPCODES {
+        instructions_map.insert(
+            opcode, Instruction::new(
+                "ILLEGAL", |registers, _memory| {
+                    registers.cpu_state = CpuState::Locked;
+                    ExecutionResult::default().without_pc_update()
+                }, 1,
+            ),
+        );
+    }
+}
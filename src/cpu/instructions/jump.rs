@@ -1,8 +1,8 @@
-use crate::cpu::instructions::{Cycles, ExecutionResult, Instruction, InstructionsMap};
+use crate::cpu::instructions::{ExecutionResult, Instruction, InstructionsMap};
 use crate::cpu::registers::Registers;
 use crate::mmu::MMU;
 
-pub(super) fn instructions_map_jump_commands(instructions_map: &mut InstructionsMap) -> () {
+pub(super) fn instructions_map_jump_commands(instructions_map: &mut InstructionsMap) {
     instructions_map_jump_commands_jp(instructions_map);
     instructions_map_jump_commands_jr(instructions_map);
     instructions_map_jump_commands_ret(instructions_map);
@@ -10,28 +10,28 @@ pub(super) fn instructions_map_jump_commands(instructions_map: &mut Instructions
     instructions_map_jump_commands_rst(instructions_map);
 }
 
-fn instructions_map_jump_commands_jp(instructions_map: &mut InstructionsMap) -> () {
+fn instructions_map_jump_commands_jp(instructions_map: &mut InstructionsMap) {
     fn jp(registers: &mut Registers, address: u16) { registers.pc = address }
 
     instructions_map.insert(
         0xc2, Instruction::new(
             "JP NZ, a16", |registers, memory| {
                 if !registers.f.z {
-                    jp(registers, memory.read_word(registers.pc + 1));
+                    jp(registers, memory.read_word(registers.pc.wrapping_add(1)));
                     ExecutionResult::default().without_pc_update()
                 } else {
                     ExecutionResult::default().without_action()
                 }
-            }, Cycles::new(16).with_not_taken(12), 3,
+            }, 3,
         ),
     );
 
     instructions_map.insert(
         0xc3, Instruction::new(
             "JP a16", |registers, memory| {
-                jp(registers, memory.read_word(registers.pc + 1));
+                jp(registers, memory.read_word(registers.pc.wrapping_add(1)));
                 ExecutionResult::default().without_pc_update()
-            }, Cycles::new(16), 3,
+            }, 3,
         ),
     );
 
@@ -39,12 +39,12 @@ fn instructions_map_jump_commands_jp(instructions_map: &mut InstructionsMap) ->
         0xca, Instruction::new(
             "JP Z, a16", |registers, memory| {
                 if registers.f.z {
-                    jp(registers, memory.read_word(registers.pc + 1));
+                    jp(registers, memory.read_word(registers.pc.wrapping_add(1)));
                     ExecutionResult::default().without_pc_update()
                 } else {
                     ExecutionResult::default().without_action()
                 }
-            }, Cycles::new(16).with_not_taken(12), 3,
+            }, 3,
         ),
     );
 
@@ -52,12 +52,12 @@ fn instructions_map_jump_commands_jp(instructions_map: &mut InstructionsMap) ->
         0xd2, Instruction::new(
             "JP NC, a16", |registers, memory| {
                 if !registers.f.c {
-                    jp(registers, memory.read_word(registers.pc + 1));
+                    jp(registers, memory.read_word(registers.pc.wrapping_add(1)));
                     ExecutionResult::default().without_pc_update()
                 } else {
                     ExecutionResult::default().without_action()
                 }
-            }, Cycles::new(16).with_not_taken(12), 3,
+            }, 3,
         ),
     );
 
@@ -65,34 +65,35 @@ fn instructions_map_jump_commands_jp(instructions_map: &mut InstructionsMap) ->
         0xda, Instruction::new(
             "JP C, a16", |registers, memory| {
                 if registers.f.c {
-                    jp(registers, memory.read_word(registers.pc + 1));
+                    jp(registers, memory.read_word(registers.pc.wrapping_add(1)));
                     ExecutionResult::default().without_pc_update()
                 } else {
                     ExecutionResult::default().without_action()
                 }
-            }, Cycles::new(16).with_not_taken(12), 3,
+            }, 3,
         ),
     );
 
     instructions_map.insert(
         0xe9, Instruction::new(
-            "JP (HL)", |registers, memory| {
-                jp(registers, memory.read_word(registers.get_hl()));
+            "JP (HL)", |registers, _memory| {
+                jp(registers, registers.get_hl());
                 ExecutionResult::default().without_pc_update()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 }
 
-fn instructions_map_jump_commands_jr(instructions_map: &mut InstructionsMap) -> () {
-    fn jr(registers: &mut Registers, offset: i8) { registers.pc = ((registers.pc as i16) + (offset as i16)) as u16 }
+fn instructions_map_jump_commands_jr(instructions_map: &mut InstructionsMap) {
+    /// The offset is relative to the end of the 2-byte instruction.
+    fn jr(registers: &mut Registers, offset: i8) { registers.pc = registers.pc.wrapping_add(2).wrapping_add(offset as u16) }
 
     instructions_map.insert(
         0x18, Instruction::new(
-            "JR, r8", |registers, memory| {
-                jr(registers, memory.read_byte(registers.pc + 1) as i8);
+            "JR r8", |registers, memory| {
+                jr(registers, memory.read_byte(registers.pc.wrapping_add(1)) as i8);
                 ExecutionResult::default().without_pc_update()
-            }, Cycles::new(12), 2,
+            }, 2,
         ),
     );
 
@@ -100,12 +101,12 @@ fn instructions_map_jump_commands_jr(instructions_map: &mut InstructionsMap) ->
         0x20, Instruction::new(
             "JR NZ, r8", |registers, memory| {
                 if !registers.f.z {
-                    jr(registers, memory.read_byte(registers.pc + 1) as i8);
+                    jr(registers, memory.read_byte(registers.pc.wrapping_add(1)) as i8);
                     ExecutionResult::default().without_pc_update()
                 } else {
                     ExecutionResult::default().without_action()
                 }
-            }, Cycles::new(12).with_not_taken(8), 2,
+            }, 2,
         ),
     );
 
@@ -113,12 +114,12 @@ fn instructions_map_jump_commands_jr(instructions_map: &mut InstructionsMap) ->
         0x28, Instruction::new(
             "JR Z, r8", |registers, memory| {
                 if registers.f.z {
-                    jr(registers, memory.read_byte(registers.pc + 1) as i8);
+                    jr(registers, memory.read_byte(registers.pc.wrapping_add(1)) as i8);
                     ExecutionResult::default().without_pc_update()
                 } else {
                     ExecutionResult::default().without_action()
                 }
-            }, Cycles::new(12).with_not_taken(8), 2,
+            }, 2,
         ),
     );
 
@@ -126,12 +127,12 @@ fn instructions_map_jump_commands_jr(instructions_map: &mut InstructionsMap) ->
         0x30, Instruction::new(
             "JR NC, r8", |registers, memory| {
                 if !registers.f.c {
-                    jr(registers, memory.read_byte(registers.pc + 1) as i8);
+                    jr(registers, memory.read_byte(registers.pc.wrapping_add(1)) as i8);
                     ExecutionResult::default().without_pc_update()
                 } else {
                     ExecutionResult::default().without_action()
                 }
-            }, Cycles::new(12).with_not_taken(8), 2,
+            }, 2,
         ),
     );
 
@@ -139,20 +140,20 @@ fn instructions_map_jump_commands_jr(instructions_map: &mut InstructionsMap) ->
         0x38, Instruction::new(
             "JR C, r8", |registers, memory| {
                 if registers.f.c {
-                    jr(registers, memory.read_byte(registers.pc + 1) as i8);
+                    jr(registers, memory.read_byte(registers.pc.wrapping_add(1)) as i8);
                     ExecutionResult::default().without_pc_update()
                 } else {
                     ExecutionResult::default().without_action()
                 }
-            }, Cycles::new(12).with_not_taken(8), 2,
+            }, 2,
         ),
     );
 }
 
-fn instructions_map_jump_commands_ret(instructions_map: &mut InstructionsMap) -> () {
+fn instructions_map_jump_commands_ret(instructions_map: &mut InstructionsMap) {
     fn ret(registers: &mut Registers, memory: &mut MMU) {
         let new_pc = memory.read_word(registers.sp);
-        registers.sp += 2;
+        registers.sp = registers.sp.wrapping_add(2);
         registers.pc = new_pc;
     }
 
@@ -161,7 +162,7 @@ fn instructions_map_jump_commands_ret(instructions_map: &mut InstructionsMap) ->
             "RET", |registers, memory| {
                 ret(registers, memory);
                 ExecutionResult::default().without_pc_update()
-            }, Cycles::new(16), 1,
+            }, 1,
         ),
     );
 
@@ -171,7 +172,7 @@ fn instructions_map_jump_commands_ret(instructions_map: &mut InstructionsMap) ->
                 ret(registers, memory);
                 registers.enable_interrupts();
                 ExecutionResult::default().without_pc_update()
-            }, Cycles::new(16), 1,
+            }, 1,
         ),
     );
 
@@ -184,7 +185,7 @@ fn instructions_map_jump_commands_ret(instructions_map: &mut InstructionsMap) ->
                 } else {
                     ExecutionResult::default().without_action()
                 }
-            }, Cycles::new(20).with_not_taken(8), 1,
+            }, 1,
         ),
     );
 
@@ -197,7 +198,7 @@ fn instructions_map_jump_commands_ret(instructions_map: &mut InstructionsMap) ->
                 } else {
                     ExecutionResult::default().without_action()
                 }
-            }, Cycles::new(20).with_not_taken(8), 1,
+            }, 1,
         ),
     );
 
@@ -210,7 +211,7 @@ fn instructions_map_jump_commands_ret(instructions_map: &mut InstructionsMap) ->
                 } else {
                     ExecutionResult::default().without_action()
                 }
-            }, Cycles::new(20).with_not_taken(8), 1,
+            }, 1,
         ),
     );
 
@@ -223,24 +224,37 @@ fn instructions_map_jump_commands_ret(instructions_map: &mut InstructionsMap) ->
                 } else {
                     ExecutionResult::default().without_action()
                 }
-            }, Cycles::new(20).with_not_taken(8), 1,
+            }, 1,
         ),
     );
 }
 
-fn instructions_map_jump_commands_call(instructions_map: &mut InstructionsMap) -> () {
+fn instructions_map_jump_commands_call(instructions_map: &mut InstructionsMap) {
     fn call(registers: &mut Registers, memory: &mut MMU, address: u16) {
-        registers.sp -= 2;
-        memory.write_word(registers.sp, registers.pc + 3);
+        registers.sp = registers.sp.wrapping_sub(2);
+        memory.write_word(registers.sp, registers.pc.wrapping_add(3));
         registers.pc = address;
     }
 
     instructions_map.insert(
         0xcd, Instruction::new(
             "CALL a16", |registers, memory| {
-                call(registers, memory, memory.read_word(registers.pc + 1));
+                call(registers, memory, memory.read_word(registers.pc.wrapping_add(1)));
                 ExecutionResult::default().without_pc_update()
-            }, Cycles::new(24), 3,
+            }, 3,
+        ),
+    );
+
+    instructions_map.insert(
+        0xc4, Instruction::new(
+            "CALL NZ, a16", |registers, memory| {
+                if !registers.f.z {
+                    call(registers, memory, memory.read_word(registers.pc.wrapping_add(1)));
+                    ExecutionResult::default().without_pc_update()
+                } else {
+                    ExecutionResult::default().without_action()
+                }
+            }, 3,
         ),
     );
 
@@ -248,12 +262,25 @@ fn instructions_map_jump_commands_call(instructions_map: &mut InstructionsMap) -
         0xcc, Instruction::new(
             "CALL Z, a16", |registers, memory| {
                 if registers.f.z {
-                    call(registers, memory, memory.read_word(registers.pc + 1));
+                    call(registers, memory, memory.read_word(registers.pc.wrapping_add(1)));
+                    ExecutionResult::default().without_pc_update()
+                } else {
+                    ExecutionResult::default().without_action()
+                }
+            }, 3,
+        ),
+    );
+
+    instructions_map.insert(
+        0xd4, Instruction::new(
+            "CALL NC, a16", |registers, memory| {
+                if !registers.f.c {
+                    call(registers, memory, memory.read_word(registers.pc.wrapping_add(1)));
                     ExecutionResult::default().without_pc_update()
                 } else {
                     ExecutionResult::default().without_action()
                 }
-            }, Cycles::new(24).with_not_taken(12), 3,
+            }, 3,
         ),
     );
 
@@ -261,20 +288,20 @@ fn instructions_map_jump_commands_call(instructions_map: &mut InstructionsMap) -
         0xdc, Instruction::new(
             "CALL C, a16", |registers, memory| {
                 if registers.f.c {
-                    call(registers, memory, memory.read_word(registers.pc + 1));
+                    call(registers, memory, memory.read_word(registers.pc.wrapping_add(1)));
                     ExecutionResult::default().without_pc_update()
                 } else {
                     ExecutionResult::default().without_action()
                 }
-            }, Cycles::new(24).with_not_taken(12), 3,
+            }, 3,
         ),
     );
 }
 
-fn instructions_map_jump_commands_rst(instructions_map: &mut InstructionsMap) -> () {
+fn instructions_map_jump_commands_rst(instructions_map: &mut InstructionsMap) {
     fn rst(registers: &mut Registers, memory: &mut MMU, address: u16) {
-        registers.sp -= 2;
-        memory.write_word(registers.sp, registers.pc + 1);
+        registers.sp = registers.sp.wrapping_sub(2);
+        memory.write_word(registers.sp, registers.pc.wrapping_add(1));
         registers.pc = address;
     }
 
@@ -283,7 +310,7 @@ fn instructions_map_jump_commands_rst(instructions_map: &mut InstructionsMap) ->
             "RST 00H", |registers, memory| {
                 rst(registers, memory, 0x00);
                 ExecutionResult::default().without_pc_update()
-            }, Cycles::new(16), 1,
+            }, 1,
         ),
     );
 
@@ -292,7 +319,7 @@ fn instructions_map_jump_commands_rst(instructions_map: &mut InstructionsMap) ->
             "RST 10H", |registers, memory| {
                 rst(registers, memory, 0x10);
                 ExecutionResult::default().without_pc_update()
-            }, Cycles::new(16), 1,
+            }, 1,
         ),
     );
 
@@ -301,7 +328,7 @@ fn instructions_map_jump_commands_rst(instructions_map: &mut InstructionsMap) ->
             "RST 20H", |registers, memory| {
                 rst(registers, memory, 0x20);
                 ExecutionResult::default().without_pc_update()
-            }, Cycles::new(16), 1,
+            }, 1,
         ),
     );
 
@@ -310,7 +337,7 @@ fn instructions_map_jump_commands_rst(instructions_map: &mut InstructionsMap) ->
             "RST 30H", |registers, memory| {
                 rst(registers, memory, 0x30);
                 ExecutionResult::default().without_pc_update()
-            }, Cycles::new(16), 1,
+            }, 1,
         ),
     );
 
@@ -319,7 +346,7 @@ fn instructions_map_jump_commands_rst(instructions_map: &mut InstructionsMap) ->
             "RST 08H", |registers, memory| {
                 rst(registers, memory, 0x08);
                 ExecutionResult::default().without_pc_update()
-            }, Cycles::new(16), 1,
+            }, 1,
         ),
     );
 
@@ -328,7 +355,7 @@ fn instructions_map_jump_commands_rst(instructions_map: &mut InstructionsMap) ->
             "RST 18H", |registers, memory| {
                 rst(registers, memory, 0x18);
                 ExecutionResult::default().without_pc_update()
-            }, Cycles::new(16), 1,
+            }, 1,
         ),
     );
 
@@ -337,7 +364,7 @@ fn instructions_map_jump_commands_rst(instructions_map: &mut InstructionsMap) ->
             "RST 28H", |registers, memory| {
                 rst(registers, memory, 0x28);
                 ExecutionResult::default().without_pc_update()
-            }, Cycles::new(16), 1,
+            }, 1,
         ),
     );
 
@@ -346,7 +373,7 @@ fn instructions_map_jump_commands_rst(instructions_map: &mut InstructionsMap) ->
             "RST 38H", |registers, memory| {
                 rst(registers, memory, 0x38);
                 ExecutionResult::default().without_pc_update()
-            }, Cycles::new(16), 1,
+            }, 1,
         ),
     );
 }
\ No newline at end of file
@@ -1,19 +1,19 @@
-use crate::cpu::instructions::{Cycles, ExecutionResult, Instruction, InstructionsMap};
+use crate::cpu::instructions::{ExecutionResult, Instruction, InstructionsMap};
 use crate::cpu::registers::Registers;
 use crate::mmu::MMU;
 
-pub(super) fn instructions_map_load_instructions(instructions_map: &mut InstructionsMap) -> () {
+pub(super) fn instructions_map_load_instructions(instructions_map: &mut InstructionsMap) {
     instructions_map_8_bit_load_instructions(instructions_map);
     instructions_map_16_bit_load_instructions(instructions_map);
 }
 
-fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsMap) -> () {
+fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsMap) {
     instructions_map.insert(
         0x02, Instruction::new(
             "LD (BC), A", |registers, memory| {
                 memory.write_byte(registers.get_bc(), registers.a);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -22,7 +22,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD (DE), A", |registers, memory| {
                 memory.write_byte(registers.get_de(), registers.a);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -30,9 +30,9 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
         0x22, Instruction::new(
             "LD (HL+), A", |registers, memory| {
                 memory.write_byte(registers.get_hl(), registers.a);
-                registers.set_hl(registers.get_hl() + 1);
+                registers.set_hl(registers.get_hl().wrapping_add(1));
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -40,47 +40,45 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
         0x32, Instruction::new(
             "LD (HL-), A", |registers, memory| {
                 memory.write_byte(registers.get_hl(), registers.a);
-                registers.set_hl(registers.get_hl() - 1);
+                registers.set_hl(registers.get_hl().wrapping_sub(1));
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
     instructions_map.insert(
         0x06, Instruction::new(
-            "LD (BC), d8", |registers, memory| {
-                memory.write_byte(registers.get_bc(), memory.read_byte(registers.pc + 1));
+            "LD B, d8", |registers, memory| {
+                registers.b = memory.read_byte(registers.pc.wrapping_add(1));
                 ExecutionResult::default()
-            }, Cycles::new(8), 2,
+            }, 2,
         ),
     );
 
     instructions_map.insert(
         0x16, Instruction::new(
-            "LD (DE), d8", |registers, memory| {
-                memory.write_byte(registers.get_de(), memory.read_byte(registers.pc + 1));
+            "LD D, d8", |registers, memory| {
+                registers.d = memory.read_byte(registers.pc.wrapping_add(1));
                 ExecutionResult::default()
-            }, Cycles::new(8), 2,
+            }, 2,
         ),
     );
 
     instructions_map.insert(
         0x26, Instruction::new(
-            "LD (HL+), d8", |registers, memory| {
-                memory.write_byte(registers.get_hl(), memory.read_byte(registers.pc + 1));
-                registers.set_hl(registers.get_hl() + 1);
+            "LD H, d8", |registers, memory| {
+                registers.h = memory.read_byte(registers.pc.wrapping_add(1));
                 ExecutionResult::default()
-            }, Cycles::new(8), 2,
+            }, 2,
         ),
     );
 
     instructions_map.insert(
         0x36, Instruction::new(
-            "LD (HL-), d8", |registers, memory| {
-                memory.write_byte(registers.get_hl(), memory.read_byte(registers.pc + 1));
-                registers.set_hl(registers.get_hl() - 1);
+            "LD (HL), d8", |registers, memory| {
+                memory.write_byte(registers.get_hl(), memory.read_byte(registers.pc.wrapping_add(1)));
                 ExecutionResult::default()
-            }, Cycles::new(8), 2,
+            }, 2,
         ),
     );
 
@@ -89,7 +87,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD A, (BC)", |registers, memory| {
                 registers.a = memory.read_byte(registers.get_bc());
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -98,7 +96,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD A, (DE)", |registers, memory| {
                 registers.a = memory.read_byte(registers.get_de());
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -106,9 +104,9 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
         0x2A, Instruction::new(
             "LD A, (HL+)", |registers, memory| {
                 registers.a = memory.read_byte(registers.get_hl());
-                registers.set_hl(registers.get_hl() + 1);
+                registers.set_hl(registers.get_hl().wrapping_add(1));
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -116,45 +114,45 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
         0x3A, Instruction::new(
             "LD A, (HL-)", |registers, memory| {
                 registers.a = memory.read_byte(registers.get_hl());
-                registers.set_hl(registers.get_hl() - 1);
+                registers.set_hl(registers.get_hl().wrapping_sub(1));
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
     instructions_map.insert(
         0x0E, Instruction::new(
             "LD C, d8", |registers, memory| {
-                registers.c = memory.read_byte(registers.pc + 1);
+                registers.c = memory.read_byte(registers.pc.wrapping_add(1));
                 ExecutionResult::default()
-            }, Cycles::new(8), 2,
+            }, 2,
         ),
     );
 
     instructions_map.insert(
         0x1E, Instruction::new(
             "LD E, d8", |registers, memory| {
-                registers.e = memory.read_byte(registers.pc + 1);
+                registers.e = memory.read_byte(registers.pc.wrapping_add(1));
                 ExecutionResult::default()
-            }, Cycles::new(8), 2,
+            }, 2,
         ),
     );
 
     instructions_map.insert(
         0x2E, Instruction::new(
             "LD L, d8", |registers, memory| {
-                registers.l = memory.read_byte(registers.pc + 1);
+                registers.l = memory.read_byte(registers.pc.wrapping_add(1));
                 ExecutionResult::default()
-            }, Cycles::new(8), 2,
+            }, 2,
         ),
     );
 
     instructions_map.insert(
         0x3E, Instruction::new(
             "LD A, d8", |registers, memory| {
-                registers.a = memory.read_byte(registers.pc + 1);
+                registers.a = memory.read_byte(registers.pc.wrapping_add(1));
                 ExecutionResult::default()
-            }, Cycles::new(8), 2,
+            }, 2,
         ),
     );
 
@@ -162,7 +160,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
         0x40, Instruction::new(
             "LD B, B", |_registers, _memory| {
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -171,7 +169,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD D, B", |registers, _memory| {
                 registers.d = registers.b;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -180,7 +178,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD H, B", |registers, _memory| {
                 registers.h = registers.b;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -189,7 +187,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD (HL), B", |registers, memory| {
                 memory.write_byte(registers.get_hl(), registers.b);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -198,7 +196,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD B, C", |registers, _memory| {
                 registers.b = registers.c;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -207,7 +205,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD D, C", |registers, _memory| {
                 registers.d = registers.c;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -216,7 +214,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD H, C", |registers, _memory| {
                 registers.h = registers.c;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -225,7 +223,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD (HL), C", |registers, memory| {
                 memory.write_byte(registers.get_hl(), registers.c);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -234,7 +232,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD B, D", |registers, _memory| {
                 registers.b = registers.d;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -242,7 +240,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
         0x52, Instruction::new(
             "LD D, D", |_registers, _memory| {
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -251,7 +249,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD H, D", |registers, _memory| {
                 registers.h = registers.d;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -260,7 +258,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD (HL), D", |registers, memory| {
                 memory.write_byte(registers.get_hl(), registers.d);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -269,7 +267,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD B, E", |registers, _memory| {
                 registers.b = registers.e;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -278,7 +276,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD D, E", |registers, _memory| {
                 registers.d = registers.e;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -287,7 +285,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD H, E", |registers, _memory| {
                 registers.h = registers.e;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -296,7 +294,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD (HL), E", |registers, memory| {
                 memory.write_byte(registers.get_hl(), registers.e);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -305,7 +303,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD B, H", |registers, _memory| {
                 registers.b = registers.h;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -314,7 +312,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD D, H", |registers, _memory| {
                 registers.d = registers.h;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -322,7 +320,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
         0x64, Instruction::new(
             "LD H, H", |_registers, _memory| {
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -331,7 +329,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD (HL), H", |registers, memory| {
                 memory.write_byte(registers.get_hl(), registers.h);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -341,7 +339,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD B, L", |registers, _memory| {
                 registers.b = registers.l;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -350,7 +348,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD D, L", |registers, _memory| {
                 registers.d = registers.l;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -359,7 +357,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD H, L", |registers, _memory| {
                 registers.h = registers.l;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -368,7 +366,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD (HL), L", |registers, memory| {
                 memory.write_byte(registers.get_hl(), registers.l);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -377,7 +375,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD B, (HL)", |registers, memory| {
                 registers.b = memory.read_byte(registers.get_hl());
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -386,7 +384,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD D, (HL)", |registers, memory| {
                 registers.d = memory.read_byte(registers.get_hl());
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -395,7 +393,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD H, (HL)", |registers, memory| {
                 registers.h = memory.read_byte(registers.get_hl());
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -404,7 +402,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD B, A", |registers, _memory| {
                 registers.b = registers.a;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -413,7 +411,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD D, A", |registers, _memory| {
                 registers.d = registers.a;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -422,7 +420,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD H, A", |registers, _memory| {
                 registers.h = registers.a;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -431,7 +429,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD (HL), A", |registers, memory| {
                 memory.write_byte(registers.get_hl(), registers.a);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -440,7 +438,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD C, B", |registers, _memory| {
                 registers.c = registers.b;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -449,7 +447,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD E, B", |registers, _memory| {
                 registers.e = registers.b;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -458,7 +456,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD L, B", |registers, _memory| {
                 registers.l = registers.b;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -467,7 +465,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD A, B", |registers, _memory| {
                 registers.a = registers.b;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -475,7 +473,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
         0x49, Instruction::new(
             "LD C, C", |_registers, _memory| {
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -484,7 +482,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD E, C", |registers, _memory| {
                 registers.e = registers.c;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -493,7 +491,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD L, C", |registers, _memory| {
                 registers.l = registers.c;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -502,7 +500,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD A, C", |registers, _memory| {
                 registers.a = registers.c;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -511,7 +509,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD C, D", |registers, _memory| {
                 registers.c = registers.d;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -520,7 +518,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD E, D", |registers, _memory| {
                 registers.e = registers.d;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -529,7 +527,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD L, D", |registers, _memory| {
                 registers.l = registers.d;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -538,7 +536,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD A, D", |registers, _memory| {
                 registers.a = registers.d;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -547,7 +545,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD C, E", |registers, _memory| {
                 registers.c = registers.e;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -555,7 +553,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
         0x5B, Instruction::new(
             "LD E, E", |_registers, _memory| {
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -564,7 +562,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD L, E", |registers, _memory| {
                 registers.l = registers.e;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -573,7 +571,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD A, E", |registers, _memory| {
                 registers.a = registers.e;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -582,7 +580,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD C, H", |registers, _memory| {
                 registers.c = registers.h;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -591,7 +589,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD E, H", |registers, _memory| {
                 registers.e = registers.h;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -600,7 +598,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD L, H", |registers, _memory| {
                 registers.l = registers.h;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -609,7 +607,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD A, H", |registers, _memory| {
                 registers.a = registers.h;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -618,7 +616,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD C, L", |registers, _memory| {
                 registers.c = registers.l;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -627,7 +625,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD E, L", |registers, _memory| {
                 registers.e = registers.l;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -635,16 +633,16 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
         0x6D, Instruction::new(
             "LD L, L", |_registers, _memory| {
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
     instructions_map.insert(
         0x7D, Instruction::new(
             "LD A, L", |registers, _memory| {
-                registers.a = registers.c;
+                registers.a = registers.l;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -653,7 +651,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD C, (HL)", |registers, memory| {
                 registers.c = memory.read_byte(registers.get_hl());
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -662,7 +660,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD E, (HL)", |registers, memory| {
                 registers.e = memory.read_byte(registers.get_hl());
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -671,7 +669,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD L, (HL)", |registers, memory| {
                 registers.l = memory.read_byte(registers.get_hl());
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -680,7 +678,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD A, (HL)", |registers, memory| {
                 registers.a = memory.read_byte(registers.get_hl());
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 
@@ -689,7 +687,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD C, A", |registers, _memory| {
                 registers.c = registers.a;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -698,7 +696,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD E, A", |registers, _memory| {
                 registers.e = registers.a;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -707,7 +705,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LD L, A", |registers, _memory| {
                 registers.l = registers.a;
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
@@ -715,26 +713,25 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
         0x7F, Instruction::new(
             "LD A, A", |_registers, _memory| {
                 ExecutionResult::default()
-            }, Cycles::new(4), 1,
+            }, 1,
         ),
     );
 
     instructions_map.insert(
         0xE0, Instruction::new(
             "LDH (a8), A", |registers, memory| {
-                registers.a = memory.read_byte(registers.pc + 1);
-                memory.write_byte(0xFF00 | memory.read_byte(registers.pc + 1) as u16, registers.a);
+                memory.write_byte(0xFF00 | memory.read_byte(registers.pc.wrapping_add(1)) as u16, registers.a);
                 ExecutionResult::default()
-            }, Cycles::new(12), 2,
+            }, 2,
         ),
     );
 
     instructions_map.insert(
         0xF0, Instruction::new(
             "LDH A, (a8)", |registers, memory| {
-                registers.a = memory.read_byte(0xFF00 | memory.read_byte(registers.pc + 1) as u16);
+                registers.a = memory.read_byte(0xFF00 | memory.read_byte(registers.pc.wrapping_add(1)) as u16);
                 ExecutionResult::default()
-            }, Cycles::new(12), 2,
+            }, 2,
         ),
     );
 
@@ -743,7 +740,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LDH (C), A", |registers, memory| {
                 memory.write_byte(0xFF00 | registers.c as u16, registers.a);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
             // NOTE(henrick): https://www.pastraiser.com/cpu/gameboy/gameboy_opcodes.html said 2 bytes,
             // but https://stackoverflow.com/questions/41353869/length-of-instruction-ld-a-c-in-gameboy-z80-processor
             // said first one is wrong and it should be 1 byte
@@ -755,7 +752,7 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
             "LDH A, (C)", |registers, memory| {
                 registers.a = memory.read_byte(0xFF00 | registers.c as u16);
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
             // NOTE(henrick): https://www.pastraiser.com/cpu/gameboy/gameboy_opcodes.html said 2 bytes,
             // but https://stackoverflow.com/questions/41353869/length-of-instruction-ld-a-c-in-gameboy-z80-processor
             // said first one is wrong and it should be 1 byte
@@ -765,103 +762,104 @@ fn instructions_map_8_bit_load_instructions(instructions_map: &mut InstructionsM
     instructions_map.insert(
         0xEA, Instruction::new(
             "LD (a16), A", |registers, memory| {
-                memory.write_byte(memory.read_word(registers.pc + 1), registers.a);
+                memory.write_byte(memory.read_word(registers.pc.wrapping_add(1)), registers.a);
                 ExecutionResult::default()
-            }, Cycles::new(16), 3,
+            }, 3,
         ),
     );
 
     instructions_map.insert(
         0xFA, Instruction::new(
             "LD A, (a16)", |registers, memory| {
-                registers.a = memory.read_byte(memory.read_word(registers.pc + 1));
+                registers.a = memory.read_byte(memory.read_word(registers.pc.wrapping_add(1)));
                 ExecutionResult::default()
-            }, Cycles::new(16), 3,
+            }, 3,
         ),
     );
 }
 
-fn instructions_map_16_bit_load_instructions(instructions_map: &mut InstructionsMap) -> () {
+fn instructions_map_16_bit_load_instructions(instructions_map: &mut InstructionsMap) {
     instructions_map_16_bit_load_ld_instructions(instructions_map);
     instructions_map_16_bit_load_pop_instructions(instructions_map);
     instructions_map_16_bit_load_push_instructions(instructions_map);
 }
 
-fn instructions_map_16_bit_load_ld_instructions(instructions_map: &mut InstructionsMap) -> () {
+fn instructions_map_16_bit_load_ld_instructions(instructions_map: &mut InstructionsMap) {
     instructions_map.insert(
         0x01, Instruction::new(
             "LD BC, d16", |registers, memory| {
-                registers.set_bc(memory.read_word(registers.pc + 1));
+                registers.set_bc(memory.read_word(registers.pc.wrapping_add(1)));
                 ExecutionResult::default()
-            }, Cycles::new(12), 3,
+            }, 3,
         ),
     );
 
     instructions_map.insert(
         0x11, Instruction::new(
             "LD DE, d16", |registers, memory| {
-                registers.set_de(memory.read_word(registers.pc + 1));
+                registers.set_de(memory.read_word(registers.pc.wrapping_add(1)));
                 ExecutionResult::default()
-            }, Cycles::new(12), 3,
+            }, 3,
         ),
     );
 
     instructions_map.insert(
         0x21, Instruction::new(
             "LD HL, d16", |registers, memory| {
-                registers.set_hl(memory.read_word(registers.pc + 1));
+                registers.set_hl(memory.read_word(registers.pc.wrapping_add(1)));
                 ExecutionResult::default()
-            }, Cycles::new(12), 3,
+            }, 3,
         ),
     );
 
     instructions_map.insert(
         0x31, Instruction::new(
             "LD SP, d16", |registers, memory| {
-                memory.write_word(registers.sp, memory.read_word(registers.pc + 1));
+                registers.sp = memory.read_word(registers.pc.wrapping_add(1));
                 ExecutionResult::default()
-            }, Cycles::new(12), 3,
+            }, 3,
         ),
     );
 
     instructions_map.insert(
         0x08, Instruction::new(
             "LD (a16), SP", |registers, memory| {
-                memory.write_word(memory.read_word(registers.pc + 1), registers.sp);
+                memory.write_word(memory.read_word(registers.pc.wrapping_add(1)), registers.sp);
                 ExecutionResult::default()
-            }, Cycles::new(20), 3,
+            }, 3,
         ),
     );
 
     instructions_map.insert(
         0xF8, Instruction::new(
             "LD HL, SP+r8", |registers, memory| {
-                let r8 = memory.read_byte(registers.pc + 1) as i16;
-                let sp = registers.sp as i16;
-                let sp_plus_r8 = sp.wrapping_add(r8);
-                registers.f.h = ((sp & 0x0F) + (r8 & 0x0F)) & 0x10 != 0;
-                registers.f.c = ((sp as u16) + (r8 as u16)) & 0x100 != 0;
-                registers.set_hl(sp_plus_r8 as u16);
+                let r8 = memory.read_byte(registers.pc.wrapping_add(1)) as i8 as u16;
+                let sp = registers.sp;
+                registers.f.z = false;
+                registers.f.n = false;
+                registers.f.h = (sp & 0x0F) + (r8 & 0x0F) > 0x0F;
+                registers.f.c = (sp & 0xFF) + (r8 & 0xFF) > 0xFF;
+                registers.set_hl(sp.wrapping_add(r8));
                 ExecutionResult::default()
-            }, Cycles::new(12), 2,
+            }, 2,
         ),
     );
 
     instructions_map.insert(
         0xF9, Instruction::new(
-            "LD SP, HL", |registers, memory| {
-                memory.write_word(registers.sp, registers.get_hl());
+            "LD SP, HL", |registers, _memory| {
+                registers.sp = registers.get_hl();
                 ExecutionResult::default()
-            }, Cycles::new(8), 1,
+            }, 1,
         ),
     );
 }
 
-fn instructions_map_16_bit_load_pop_instructions(instructions_map: &mut InstructionsMap) -> () {
+fn instructions_map_16_bit_load_pop_instructions(instructions_map: &mut InstructionsMap) {
     fn pop(registers: &mut Registers, memory: &MMU) -> u16 {
-        let sp = memory.read_word(registers.sp);
-        registers.sp += 2;
-        sp
+        let d16 = memory.read_word(registers.sp);
+        registers.sp = registers.sp.wrapping_add(2);
+        d16
     }
 
     instructions_map.insert(
@@ -870,7 +868,7 @@ fn instructions_map_16_bit_load_pop_instructions(instructions_map: &mut Instruct
                 let d16 = pop(registers, memory);
                 registers.set_bc(d16);
                 ExecutionResult::default()
-            }, Cycles::new(12), 1,
+            }, 1,
         ),
     );
 
@@ -880,7 +878,7 @@ fn instructions_map_16_bit_load_pop_instructions(instructions_map: &mut Instruct
                 let d16 = pop(registers, memory);
                 registers.set_de(d16);
                 ExecutionResult::default()
-            }, Cycles::new(12), 1,
+            }, 1,
         ),
     );
 
@@ -890,7 +888,7 @@ fn instructions_map_16_bit_load_pop_instructions(instructions_map: &mut Instruct
                 let d16 = pop(registers, memory);
                 registers.set_hl(d16);
                 ExecutionResult::default()
-            }, Cycles::new(12), 1,
+            }, 1,
         ),
     );
 
@@ -900,15 +898,15 @@ fn instructions_map_16_bit_load_pop_instructions(instructions_map: &mut Instruct
                 let d16 = pop(registers, memory);
                 registers.set_af(d16);
                 ExecutionResult::default()
-            }, Cycles::new(12), 1,
+            }, 1,
         ),
     );
 }
 
-fn instructions_map_16_bit_load_push_instructions(instructions_map: &mut InstructionsMap) -> () {
+fn instructions_map_16_bit_load_push_instructions(instructions_map: &mut InstructionsMap) {
     fn push(registers: &mut Registers, memory: &mut MMU, d16: u16) {
+        registers.sp = registers.sp.wrapping_sub(2);
         memory.write_word(registers.sp, d16);
-        registers.sp += 2;
     }
 
     instructions_map.insert(
@@ -916,7 +914,7 @@ fn instructions_map_16_bit_load_push_instructions(instructions_map: &mut Instruc
             "PUSH BC", |registers, memory| {
                 push(registers, memory, registers.get_bc());
                 ExecutionResult::default()
-            }, Cycles::new(16), 1,
+            }, 1,
         ),
     );
 
@@ -925,7 +923,7 @@ fn instructions_map_16_bit_load_push_instructions(instructions_map: &mut Instruc
             "PUSH DE", |registers, memory| {
                 push(registers, memory, registers.get_de());
                 ExecutionResult::default()
-            }, Cycles::new(16), 1,
+            }, 1,
         ),
     );
 
@@ -934,7 +932,7 @@ fn instructions_map_16_bit_load_push_instructions(instructions_map: &mut Instruc
             "PUSH HL", |registers, memory| {
                 push(registers, memory, registers.get_hl());
                 ExecutionResult::default()
-            }, Cycles::new(16), 1,
+            }, 1,
         ),
     );
 
@@ -943,7 +941,7 @@ fn instructions_map_16_bit_load_push_instructions(instructions_map: &mut Instruc
             "PUSH AF", |registers, memory| {
                 push(registers, memory, registers.get_af());
                 ExecutionResult::default()
-            }, Cycles::new(16), 1,
+            }, 1,
         ),
     );
 }
\ No newline at end of file
@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-
 use crate::cpu::registers::Registers;
 use crate::mmu::MMU;
 
@@ -41,65 +39,57 @@ impl ExecutionResult {
 
 pub type ExecuteFn = fn(&mut Registers, &mut MMU) -> ExecutionResult;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-pub(crate) struct Cycles {
-    pub(crate) taken: u8,
-    pub(crate) not_taken: u8,
-}
-
-impl Cycles {
-    pub(crate) fn new(taken: u8) -> Self {
-        Cycles {
-            taken,
-            not_taken: taken,
-        }
-    }
-
-    fn with_not_taken(self, not_taken: u8) -> Self {
-        Cycles {
-            not_taken,
-            ..self
-        }
-    }
-}
-
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, Copy, Clone)]
 pub(crate) struct Instruction {
     pub(crate) mnemonic: &'static str,
     pub(crate) execute: ExecuteFn,
-    pub(crate) cycles: Cycles,
     pub(crate) bytes: u8,
 }
 
 impl Instruction {
-    pub(crate) fn new(mnemonic: &'static str, execute: ExecuteFn, cycles: Cycles, bytes: u8) -> Self {
+    pub(crate) fn new(mnemonic: &'static str, execute: ExecuteFn, bytes: u8) -> Self {
         Instruction {
             mnemonic,
             execute,
-            cycles,
             bytes,
         }
     }
 }
 
-pub(super) type InstructionsMap = HashMap<u8, Instruction>;
+/// Instructions indexed by opcode.
+pub(super) struct InstructionsMap {
+    instructions: [Option<Instruction>; 256],
+}
+
+impl InstructionsMap {
+    fn new() -> Self {
+        InstructionsMap {
+            instructions: [None; 256],
+        }
+    }
+
+    pub(super) fn insert(&mut self, opcode: u8, instruction: Instruction) {
+        self.instructions[opcode as usize] = Some(instruction);
+    }
+
+    pub(super) fn get(&self, opcode: u8) -> Option<Instruction> {
+        self.instructions[opcode as usize]
+    }
+}
 
+/// Instructions decoded from the unprefixed opcodes and from the opcodes following a 0xCB prefix.
 pub(super) struct InstructionsMapsManager {
     default_map: InstructionsMap,
     prefix_cb_map: InstructionsMap,
-    state: InstructionsMapState,
-}
-
-#[derive(PartialEq)]
-enum InstructionsMapState {
-    Default,
-    PrefixCB,
 }
 
 impl InstructionsMapsManager {
+    /// Prefix of the opcodes decoded from `prefix_cb_map`.
+    pub(super) const PREFIX_CB: u8 = 0xCB;
+
     pub(super) fn new() -> Self {
-        let mut default_map = HashMap::new(); // Initialize with your default instructions map
-        let mut prefix_cb_map = HashMap::new(); // Initialize with your prefix CB instructions map
+        let mut default_map = InstructionsMap::new();
+        let mut prefix_cb_map = InstructionsMap::new();
 
         control::instructions_map_control_commands(&mut default_map);
         jump::instructions_map_jump_commands(&mut default_map);
@@ -110,20 +100,79 @@ impl InstructionsMapsManager {
         InstructionsMapsManager {
             default_map,
             prefix_cb_map,
-            state: InstructionsMapState::Default,
         }
     }
 
-    pub(super) fn get_instruction_map(&mut self) -> &mut InstructionsMap {
-        match self.state {
-            InstructionsMapState::Default => &mut self.default_map,
-            InstructionsMapState::PrefixCB => &mut self.prefix_cb_map,
-        }
+    pub(super) fn get(&self, opcode: u8) -> Option<Instruction> {
+        self.default_map.get(opcode)
     }
 
-    pub(super) fn is_default_map(&self) -> bool { self.state == InstructionsMapState::Default }
+    /// Whether `opcode` is one of the unused opcodes locking the CPU up.
+    pub(super) fn is_illegal(opcode: u8) -> bool {
+        control::ILLEGAL_OPCODES.contains(&opcode)
+    }
 
-    pub(super) fn set_prefix_cb_state(&mut self) { self.state = InstructionsMapState::PrefixCB }
+    /// Instruction of the 2-byte `0xCB opcode` sequence.
+    pub(super) fn get_prefixed(&self, opcode: u8) -> Option<Instruction> {
+        self.prefix_cb_map.get(opcode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::control::ILLEGAL_OPCODES;
+    use super::InstructionsMapsManager;
+
+    /// Length in bytes of every unprefixed opcode, 0 for the illegal ones and the 0xCB prefix.
+    #[rustfmt::skip]
+    const BASE_LENGTHS: [u8; 256] = [
+    //  x0 x1 x2 x3 x4 x5 x6 x7 x8 x9 xA xB xC xD xE xF
+        1, 3, 1, 1, 1, 1, 2, 1, 3, 1, 1, 1, 1, 1, 2, 1, // 0x
+        2, 3, 1, 1, 1, 1, 2, 1, 2, 1, 1, 1, 1, 1, 2, 1, // 1x
+        2, 3, 1, 1, 1, 1, 2, 1, 2, 1, 1, 1, 1, 1, 2, 1, // 2x
+        2, 3, 1, 1, 1, 1, 2, 1, 2, 1, 1, 1, 1, 1, 2, 1, // 3x
+        1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, // 4x
+        1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, // 5x
+        1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, // 6x
+        1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, // 7x
+        1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, // 8x
+        1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, // 9x
+        1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, // Ax
+        1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, // Bx
+        1, 1, 3, 3, 3, 1, 2, 1, 1, 1, 3, 0, 3, 3, 2, 1, // Cx
+        1, 1, 3, 0, 3, 1, 2, 1, 1, 1, 3, 0, 3, 0, 2, 1, // Dx
+        2, 1, 1, 0, 0, 1, 2, 1, 2, 1, 3, 0, 0, 0, 2, 1, // Ex
+        2, 1, 1, 1, 0, 1, 2, 1, 2, 1, 3, 1, 0, 0, 2, 1, // Fx
+    ];
+
+    #[test]
+    fn every_opcode_is_mapped_with_its_length() {
+        let maps = InstructionsMapsManager::new();
+
+        let mut legal = 0;
+        for opcode in 0..=255u8 {
+            if opcode == InstructionsMapsManager::PREFIX_CB || ILLEGAL_OPCODES.contains(&opcode) {
+                continue;
+            }
+            let instruction = maps.get(opcode)
+                .unwrap_or_else(|| panic!("Opcode {:#04X} is not mapped", opcode));
+            assert_eq!(instruction.bytes, BASE_LENGTHS[opcode as usize],
+                       "Wrong length for {:#04X} {}", opcode, instruction.mnemonic);
+            legal += 1;
+        }
+        // The 0xCB prefix makes up the 245 legal unprefixed opcodes.
+        assert_eq!(legal + 1, 245);
+
+        for opcode in ILLEGAL_OPCODES {
+            let instruction = maps.get(opcode)
+                .unwrap_or_else(|| panic!("Illegal opcode {:#04X} is not mapped", opcode));
+            assert_eq!(instruction.bytes, 1);
+        }
 
-    pub(super) fn reset_state(&mut self) { self.state = InstructionsMapState::Default }
-}
\ No newline at end of file
+        for opcode in 0..=255u8 {
+            let instruction = maps.get_prefixed(opcode)
+                .unwrap_or_else(|| panic!("Opcode 0xCB {:#04X} is not mapped", opcode));
+            assert_eq!(instruction.bytes, 2, "Wrong length for 0xCB {:#04X} {}", opcode, instruction.mnemonic);
+        }
+    }
+}
@@ -1,26 +1,33 @@
-use std::thread;
-use std::time::{Duration, Instant};
-
-use log::{debug, error, warn};
+use log::debug;
 
 use crate::cpu::instructions::{Instruction, InstructionsMapsManager};
-use crate::cpu::registers::{CpuState, Registers};
+use crate::cpu::timings::{Cycles, BASE_CYCLES, CB_CYCLES};
 use crate::mmu::MMU;
+use crate::save_state::{Snapshot, StateReader, StateWriter};
+use crate::symbols::Symbols;
+
+pub(crate) use disasm::{dump as dump_disassembly, Disassembler};
+pub(crate) use registers::{CpuState, Registers};
+pub(crate) use trace::{format_state as format_trace_state, Trace, Tracer};
 
+mod disasm;
 mod instructions;
 mod registers;
+mod timings;
+mod trace;
 
-fn cycles_to_time(cycles: u8) -> Duration {
-    let clock_speed = 4_194_304.0;
-    let time = cycles as f64 / clock_speed;
-    let seconds = time.floor() as u64;
-    let nanoseconds = ((time - seconds as f64) * 1_000_000_000.0) as u32;
-    Duration::new(seconds, nanoseconds)
-}
+/// T-cycles needed to push PC and jump to an interrupt vector (5 M-cycles).
+const INTERRUPT_DISPATCH_CYCLES: u8 = 20;
+
+/// T-cycles elapsed during a step while the CPU is halted or stopped (1 M-cycle).
+const IDLE_CYCLES: u8 = 4;
 
 pub(crate) struct CPU {
     registers: Registers,
     instructions_maps_manager: InstructionsMapsManager,
+    tracer: Option<Box<dyn Tracer>>,
+    /// Labels of the addresses in the debug log.
+    symbols: Option<Symbols>,
 }
 
 impl CPU {
@@ -28,63 +35,379 @@ impl CPU {
         CPU {
             registers: Registers::new(),
             instructions_maps_manager: InstructionsMapsManager::new(),
+            tracer: None,
+            symbols: None,
+        }
+    }
+
+    /// Passes the state of the CPU to `tracer` before every instruction executed.
+    pub(crate) fn with_tracer(self, tracer: Box<dyn Tracer>) -> Self {
+        CPU {
+            tracer: Some(tracer),
+            ..self
+        }
+    }
+
+    /// Locates the addresses of the debug log relative to the labels of `symbols`.
+    pub(crate) fn with_symbols(self, symbols: Option<Symbols>) -> Self {
+        CPU { symbols, ..self }
+    }
+
+    pub(crate) fn registers(&self) -> &Registers {
+        &self.registers
+    }
+
+    pub(crate) fn registers_mut(&mut self) -> &mut Registers {
+        &mut self.registers
+    }
+
+    /// `address`, followed by its location relative to the labels if there are symbols.
+    fn describe_address(&self, address: u16, mmu: &MMU) -> String {
+        let location = self.symbols.as_ref().and_then(|symbols| symbols.locate(address, mmu.cartridge.current_rom_bank()));
+        match location {
+            Some(location) => format!("0x{:0>4x} ({})", address, location),
+            None => format!("0x{:0>4x}", address),
         }
     }
 
     fn fetch(&mut self, mmu: &MMU) -> u8 { mmu.read_byte(self.registers.pc) }
 
-    fn decode(&mut self, byte: u8) -> Option<Instruction> {
-        let instruction = self.instructions_maps_manager.get_instruction_map().get(&byte).cloned();
-        if byte == 0xCB && self.instructions_maps_manager.is_default_map() {
-            self.instructions_maps_manager.set_prefix_cb_state();
-        } else {
-            self.instructions_maps_manager.reset_state();
-        };
-        instruction
+    /// Instruction at `address`, if its opcode is known, read without triggering watchpoints.
+    pub(crate) fn instruction_at(&self, address: u16, mmu: &MMU) -> Option<Instruction> {
+        match mmu.peek_byte(address) {
+            InstructionsMapsManager::PREFIX_CB => self.instructions_maps_manager.get_prefixed(mmu.peek_byte(address.wrapping_add(1))),
+            byte => self.instructions_maps_manager.get(byte),
+        }
     }
 
-    fn execute(&mut self, instruction: &Instruction, mmu: &mut MMU) -> (bool, u8) {
+    /// Decodes the instruction starting with `byte` at `address`, reading the second byte of
+    /// CB-prefixed instructions so that they are executed as a whole.
+    ///
+    /// Both tables map every opcode, the illegal ones locking the CPU up, so decoding never fails.
+    fn decode(&self, address: u16, byte: u8, mmu: &MMU) -> (Instruction, Cycles) {
+        if byte == InstructionsMapsManager::PREFIX_CB {
+            let opcode = mmu.read_byte(address.wrapping_add(1));
+            let instruction = self.instructions_maps_manager.get_prefixed(opcode)
+                .unwrap_or_else(|| unreachable!("Opcode 0xCB {:#04X} is not mapped", opcode));
+            return (instruction, CB_CYCLES[opcode as usize]);
+        }
+        let instruction = self.instructions_maps_manager.get(byte)
+            .unwrap_or_else(|| unreachable!("Opcode {:#04X} is not mapped", byte));
+        (instruction, BASE_CYCLES[byte as usize])
+    }
+
+    fn execute(&mut self, instruction: &Instruction, cycles: Cycles, mmu: &mut MMU) -> (bool, u8) {
         let result = (instruction.execute)(&mut self.registers, mmu);
         match result.action_taken {
-            true => (result.update_pc, instruction.cycles.taken),
-            false => (result.update_pc, instruction.cycles.not_taken),
+            true => (result.update_pc, cycles.taken),
+            false => (result.update_pc, cycles.not_taken),
         }
     }
 
-    pub(crate) fn step(&mut self, mmu: &mut MMU) {
-        debug!("{:?}", self.registers);
-
-        if self.registers.cpu_state == CpuState::Running {
-            let start_time = Instant::now();
-            let pc = self.registers.pc;
-            let byte = self.fetch(mmu);
-            debug!("Fetch:   @0x{:0>4x} -> 0x{:0>2x}", pc, byte);
-
-            let instruction = match self.decode(byte) {
-                Some(instruction) => instruction,
-                None => {
-                    error!("Fatal: Unknown instruction for byte 0x{:0>2x}", byte);
-                    std::process::exit(1);
-                }
-            };
-            debug!("Decode:  0x{:0>2x} = {:?}", byte, instruction.mnemonic);
-
-            let (pc_update, cycles) = self.execute(&instruction, mmu);
-
-            debug!("Execute: {:?} : {} cycles", instruction.mnemonic, cycles);
-
-            if pc_update {
-                self.registers.pc += instruction.bytes as u16;
-            }
-            let cur_time = Instant::now();
-            let delta_time = cur_time - start_time;
-            let step_duration = cycles_to_time(cycles);
-            if step_duration < delta_time {
-                warn!("Overshoot: {} ns > {} ns", delta_time.as_nanos(), step_duration.as_nanos());
-            } else {
-                let wait_for = step_duration - delta_time;
-                thread::sleep(wait_for);
-            }
+    /// Services the highest priority pending interrupt, if any.
+    ///
+    /// A pending interrupt always wakes the CPU up from `HALT`, but it is only dispatched when IME
+    /// is set: IME is cleared, the interrupt is acknowledged in IF, PC is pushed on the stack and
+    /// execution resumes at the interrupt vector. Returns the cycles spent dispatching.
+    fn service_interrupts(&mut self, mmu: &mut MMU) -> Option<u8> {
+        if self.registers.cpu_state == CpuState::Locked {
+            return None;
+        }
+        let interrupt = mmu.interrupts.pending()?;
+        if self.registers.cpu_state == CpuState::Halted {
+            self.registers.cpu_state = CpuState::Running;
         }
+        if !self.registers.interrupts_enabled {
+            return None;
+        }
+        debug!("Interrupt: {:?} -> {}", interrupt, self.describe_address(interrupt.vector(), mmu));
+        self.registers.interrupts_enabled = false;
+        mmu.interrupts.acknowledge(interrupt);
+        self.registers.sp = self.registers.sp.wrapping_sub(2);
+        mmu.write_word(self.registers.sp, self.registers.pc);
+        self.registers.pc = interrupt.vector();
+        Some(INTERRUPT_DISPATCH_CYCLES)
+    }
+
+    fn run_instruction(&mut self, mmu: &mut MMU) -> u8 {
+        let enable_interrupts = self.registers.interrupts_enable_scheduled;
+        if let Some(tracer) = &mut self.tracer {
+            tracer.trace(&self.registers, mmu);
+        }
+        let pc = self.registers.pc;
+        let byte = self.fetch(mmu);
+        debug!("Fetch:   @{} -> 0x{:0>2x}", self.describe_address(pc, mmu), byte);
+
+        if self.registers.halt_bug {
+            // PC failed to increment after the fetch: operands are read starting from the opcode
+            // itself and the instruction ends one byte earlier than it should.
+            debug!("HALT bug: PC not incremented after fetch @{}", self.describe_address(pc, mmu));
+            self.registers.halt_bug = false;
+            self.registers.pc = pc.wrapping_sub(1);
+        }
+
+        let (instruction, cycles) = self.decode(self.registers.pc, byte, mmu);
+        debug!("Decode:  0x{:0>2x} = {:?}", byte, instruction.mnemonic);
+
+        let (pc_update, cycles) = self.execute(&instruction, cycles, mmu);
+
+        debug!("Execute: {:?} : {} cycles", instruction.mnemonic, cycles);
+
+        if pc_update {
+            self.registers.pc = self.registers.pc.wrapping_add(instruction.bytes as u16);
+        }
+        if enable_interrupts && self.registers.interrupts_enable_scheduled {
+            self.registers.enable_interrupts();
+        }
+        cycles
+    }
+
+    /// Runs a single step of the CPU and returns the number of T-cycles it took.
+    ///
+    /// A step either dispatches a pending interrupt, executes one instruction, or idles while the
+    /// CPU is halted or stopped.
+    pub(crate) fn step(&mut self, mmu: &mut MMU) -> u8 {
+        let cycles = match self.service_interrupts(mmu) {
+            Some(cycles) => cycles,
+            None => match self.registers.cpu_state {
+                CpuState::Running => self.run_instruction(mmu),
+                CpuState::Halted | CpuState::Stopped | CpuState::Locked => IDLE_CYCLES,
+            },
+        };
+        mmu.tick(cycles);
+        cycles
     }
-}
\ No newline at end of file
+}
+
+/// Only the registers hold state: the instructions maps are built identically by every CPU.
+impl Snapshot for CPU {
+    fn save_state(&self, state: &mut StateWriter) {
+        self.registers.save_state(state);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.registers.load_state(state)
+    }
+}
+
+/// CPU about to run `program` from 0xC000, with every interrupt enabled in IE.
+#[cfg(test)]
+pub(crate) fn machine(program: &[u8]) -> (CPU, MMU) {
+    let mut mmu = MMU::new();
+    for (offset, &byte) in program.iter().enumerate() {
+        mmu.write_byte(0xC000 + offset as u16, byte);
+    }
+    mmu.write_byte(crate::interrupts::Interrupts::IE_ADDRESS, 0x1F);
+    let mut cpu = CPU::new();
+    cpu.registers.pc = 0xC000;
+    (cpu, mmu)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::interrupts::{Interrupt, Interrupts};
+
+    #[test]
+    fn dispatches_the_highest_priority_interrupt_in_20_cycles() {
+        let (mut cpu, mut mmu) = machine(&[0x00]);
+        cpu.registers.interrupts_enabled = true;
+        mmu.interrupts.request(Interrupt::Joypad);
+        mmu.interrupts.request(Interrupt::Timer);
+        mmu.interrupts.request(Interrupt::LcdStat);
+
+        assert_eq!(cpu.step(&mut mmu), 20);
+        assert_eq!(cpu.registers.pc, 0x0048);
+        assert_eq!(cpu.registers.sp, 0xFFFC);
+        assert_eq!(mmu.read_word(0xFFFC), 0xC000);
+        assert!(!cpu.registers.interrupts_enabled);
+    }
+
+    #[test]
+    fn dispatch_acknowledges_only_the_serviced_interrupt() {
+        let (mut cpu, mut mmu) = machine(&[0x00]);
+        cpu.registers.interrupts_enabled = true;
+        mmu.interrupts.request(Interrupt::VBlank);
+        mmu.interrupts.request(Interrupt::Serial);
+
+        cpu.step(&mut mmu);
+        assert_eq!(cpu.registers.pc, 0x0040);
+        assert_eq!(mmu.read_byte(Interrupts::IF_ADDRESS) & 0x1F, Interrupt::Serial.mask());
+    }
+
+    #[test]
+    fn interrupts_disabled_in_ie_are_not_dispatched() {
+        let (mut cpu, mut mmu) = machine(&[0x00]);
+        cpu.registers.interrupts_enabled = true;
+        mmu.write_byte(Interrupts::IE_ADDRESS, Interrupt::VBlank.mask());
+        mmu.interrupts.request(Interrupt::Timer);
+
+        assert_eq!(cpu.step(&mut mmu), 4);
+        assert_eq!(cpu.registers.pc, 0xC001);
+    }
+
+    #[test]
+    fn pending_interrupt_wakes_up_from_halt_without_dispatch_when_ime_is_clear() {
+        let program = [
+            0x76, // HALT
+            0x3C, // INC A
+        ];
+        let (mut cpu, mut mmu) = machine(&program);
+        cpu.registers.interrupts_enabled = false;
+        cpu.registers.a = 0;
+
+        cpu.step(&mut mmu);
+        assert_eq!(cpu.registers.cpu_state, CpuState::Halted);
+        assert_eq!(cpu.step(&mut mmu), IDLE_CYCLES);
+        assert_eq!(cpu.registers.pc, 0xC001);
+
+        mmu.interrupts.request(Interrupt::Timer);
+        cpu.step(&mut mmu);
+        assert_eq!(cpu.registers.cpu_state, CpuState::Running);
+        assert_eq!(cpu.registers.pc, 0xC002);
+        assert_eq!(cpu.registers.a, 1);
+        assert_eq!(mmu.read_byte(Interrupts::IF_ADDRESS) & 0x1F, Interrupt::Timer.mask());
+    }
+
+    #[test]
+    fn ei_followed_by_di_never_dispatches() {
+        let program = [
+            0xFB, // EI
+            0xF3, // DI
+            0x00, // NOP
+        ];
+        let (mut cpu, mut mmu) = machine(&program);
+        cpu.registers.interrupts_enabled = false;
+        mmu.interrupts.request(Interrupt::VBlank);
+
+        for _ in 0..3 {
+            cpu.step(&mut mmu);
+        }
+        assert_eq!(cpu.registers.pc, 0xC003);
+        assert!(!cpu.registers.interrupts_enabled);
+    }
+
+    #[test]
+    fn ei_enables_interrupts_after_the_next_instruction() {
+        let program = [
+            0xFB, // EI
+            0x3C, // INC A
+            0x00, // NOP
+        ];
+        let (mut cpu, mut mmu) = machine(&program);
+        cpu.registers.interrupts_enabled = false;
+        cpu.registers.a = 0;
+        mmu.interrupts.request(Interrupt::VBlank);
+
+        cpu.step(&mut mmu);
+        cpu.step(&mut mmu);
+        assert_eq!(cpu.registers.a, 1);
+        assert_eq!(cpu.step(&mut mmu), INTERRUPT_DISPATCH_CYCLES);
+        assert_eq!(cpu.registers.pc, 0x0040);
+        assert_eq!(mmu.read_word(cpu.registers.sp), 0xC002);
+    }
+
+    #[test]
+    fn halt_with_ime_clear_and_pending_interrupt_reads_the_next_byte_twice() {
+        let program = [
+            0x76, // HALT
+            0x3C, // INC A
+            0x00, // NOP
+        ];
+        let (mut cpu, mut mmu) = machine(&program);
+        cpu.registers.interrupts_enabled = false;
+        cpu.registers.a = 0;
+        mmu.interrupts.request(Interrupt::Timer);
+
+        cpu.step(&mut mmu);
+        assert_eq!(cpu.registers.cpu_state, CpuState::Running);
+        cpu.step(&mut mmu);
+        assert_eq!(cpu.registers.pc, 0xC001);
+        cpu.step(&mut mmu);
+        assert_eq!(cpu.registers.pc, 0xC002);
+        assert_eq!(cpu.registers.a, 2);
+    }
+
+    #[test]
+    fn reti_enables_interrupts_immediately() {
+        let (mut cpu, mut mmu) = machine(&[0xD9]); // RETI
+        cpu.registers.interrupts_enabled = false;
+        cpu.registers.sp = 0xFFFC;
+        mmu.write_word(0xFFFC, 0xC100);
+        mmu.interrupts.request(Interrupt::Serial);
+
+        cpu.step(&mut mmu);
+        assert_eq!(cpu.registers.pc, 0xC100);
+        assert!(cpu.registers.interrupts_enabled);
+        assert_eq!(cpu.step(&mut mmu), INTERRUPT_DISPATCH_CYCLES);
+        assert_eq!(cpu.registers.pc, 0x0058);
+        assert_eq!(mmu.read_word(cpu.registers.sp), 0xC100);
+    }
+
+    #[test]
+    fn cb_prefixed_instructions_run_in_a_single_step() {
+        let (mut cpu, mut mmu) = machine(&[0xCB, 0x46]); // BIT 0,(HL)
+        cpu.registers.set_hl(0xC100);
+
+        assert_eq!(cpu.step(&mut mmu), 12);
+        assert_eq!(cpu.registers.pc, 0xC002);
+    }
+
+    #[test]
+    fn interrupts_are_not_dispatched_between_the_cb_prefix_and_its_opcode() {
+        let program = [
+            0xFB, // EI
+            0xCB, 0x46, // BIT 0,(HL)
+        ];
+        let (mut cpu, mut mmu) = machine(&program);
+        cpu.registers.interrupts_enabled = false;
+        cpu.registers.set_hl(0xC100);
+        mmu.interrupts.request(Interrupt::VBlank);
+
+        cpu.step(&mut mmu);
+        cpu.step(&mut mmu);
+        assert_eq!(cpu.registers.pc, 0xC003);
+        assert_eq!(cpu.step(&mut mmu), INTERRUPT_DISPATCH_CYCLES);
+        assert_eq!(mmu.read_word(cpu.registers.sp), 0xC003);
+    }
+
+    #[test]
+    fn operands_and_pc_wrap_around_the_end_of_memory() {
+        let (mut cpu, mut mmu) = machine(&[]);
+        mmu.write_byte(0xFFFE, 0x3E); // LD A,d8 with its operand in IE
+        cpu.registers.pc = 0xFFFE;
+
+        cpu.step(&mut mmu);
+        assert_eq!(cpu.registers.a, 0x1F);
+        assert_eq!(cpu.registers.pc, 0x0000);
+    }
+
+    /// Measures the instructions executed per second on a loop mixing common instructions.
+    ///
+    /// Run with `cargo test --release -- --ignored --nocapture instructions_per_second`.
+    #[test]
+    #[ignore]
+    fn instructions_per_second() {
+        const INSTRUCTIONS: u32 = 10_000_000;
+        let program = [
+            0x3C, // INC A
+            0x80, // ADD A,B
+            0x47, // LD B,A
+            0xCB, 0x37, // SWAP A
+            0x00, // NOP
+            0xA8, // XOR B
+            0xC3, 0x00, 0xC0, // JP 0xC000
+        ];
+        let (mut cpu, mut mmu) = machine(&program);
+
+        let start = Instant::now();
+        for _ in 0..INSTRUCTIONS {
+            cpu.step(&mut mmu);
+        }
+        let elapsed = start.elapsed();
+        println!("{} instructions in {:?}: {:.0} instructions/s",
+                 INSTRUCTIONS, elapsed, INSTRUCTIONS as f64 / elapsed.as_secs_f64());
+    }
+}
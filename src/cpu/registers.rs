@@ -1,6 +1,7 @@
 use std::fmt;
 
 use crate::cpu::registers::CpuState::Running;
+use crate::save_state::{Snapshot, StateReader, StateWriter};
 
 pub struct Flags {
     pub(crate) z: bool,
@@ -40,11 +41,13 @@ impl Flags {
     }
 }
 
-#[derive(PartialEq)]
+#[derive(Debug, PartialEq)]
 pub(crate) enum CpuState {
     Running,
     Halted,
     Stopped,
+    /// Hard lock caused by an illegal opcode, only a reset gets the CPU out of it.
+    Locked,
 }
 
 pub struct Registers {
@@ -59,6 +62,11 @@ pub struct Registers {
     pub(crate) pc: u16,
     pub(crate) sp: u16,
     pub(crate) interrupts_enabled: bool,
+    /// Set by `EI`: IME only becomes enabled after the instruction following `EI`.
+    pub(crate) interrupts_enable_scheduled: bool,
+    /// Set when `HALT` is executed with IME cleared and an interrupt pending: the next opcode
+    /// is fetched without incrementing PC, so the byte following `HALT` is read twice.
+    pub(crate) halt_bug: bool,
     pub(crate) cpu_state: CpuState,
 }
 
@@ -81,19 +89,22 @@ impl fmt::Debug for Registers {
 
 #[warn(dead_code)] // TODO(henrick) part of hardware simu
 impl Registers {
+    /// Registers as left by the DMG boot ROM, which is not emulated.
     pub(crate) fn new() -> Self {
         Registers {
-            a: 0,
-            f: Flags { z: false, n: false, h: false, c: false },
-            b: 0,
-            c: 0,
-            d: 0,
-            e: 0,
-            h: 0,
-            l: 0,
+            a: 0x01,
+            f: Flags { z: true, n: false, h: true, c: true },
+            b: 0x00,
+            c: 0x13,
+            d: 0x00,
+            e: 0xD8,
+            h: 0x01,
+            l: 0x4D,
             pc: 0x0100,
-            sp: 0,
+            sp: 0xFFFE,
             interrupts_enabled: false,
+            interrupts_enable_scheduled: false,
+            halt_bug: false,
             cpu_state: Running,
         }
     }
@@ -135,10 +146,78 @@ impl Registers {
     }
 
     pub fn enable_interrupts(&mut self) {
-        self.interrupts_enabled = true
+        self.interrupts_enabled = true;
+        self.interrupts_enable_scheduled = false;
     }
 
-    pub fn _disable_interrupts(&mut self) {
-        self.interrupts_enabled = false
+    pub fn schedule_interrupts_enable(&mut self) {
+        if !self.interrupts_enabled {
+            self.interrupts_enable_scheduled = true;
+        }
+    }
+
+    pub fn disable_interrupts(&mut self) {
+        self.interrupts_enabled = false;
+        self.interrupts_enable_scheduled = false;
+    }
+}
+
+impl Snapshot for Registers {
+    fn save_state(&self, state: &mut StateWriter) {
+        for value in [self.a, self.f.as_u8(), self.b, self.c, self.d, self.e, self.h, self.l] {
+            state.write_u8(value);
+        }
+        state.write_u16(self.pc);
+        state.write_u16(self.sp);
+        state.write_bool(self.interrupts_enabled);
+        state.write_bool(self.interrupts_enable_scheduled);
+        state.write_bool(self.halt_bug);
+        state.write_u8(match self.cpu_state {
+            CpuState::Running => 0,
+            CpuState::Halted => 1,
+            CpuState::Stopped => 2,
+            CpuState::Locked => 3,
+        });
     }
-}
\ No newline at end of file
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.a = state.read_u8()?;
+        self.f = Flags::from_u8(state.read_u8()?);
+        self.b = state.read_u8()?;
+        self.c = state.read_u8()?;
+        self.d = state.read_u8()?;
+        self.e = state.read_u8()?;
+        self.h = state.read_u8()?;
+        self.l = state.read_u8()?;
+        self.pc = state.read_u16()?;
+        self.sp = state.read_u16()?;
+        self.interrupts_enabled = state.read_bool()?;
+        self.interrupts_enable_scheduled = state.read_bool()?;
+        self.halt_bug = state.read_bool()?;
+        self.cpu_state = match state.read_u8()? {
+            0 => CpuState::Running,
+            1 => CpuState::Halted,
+            2 => CpuState::Stopped,
+            3 => CpuState::Locked,
+            value => return Err(format!("Invalid CPU state in save state: {}", value)),
+        };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_with_the_registers_left_by_the_dmg_boot_rom() {
+        let registers = Registers::new();
+        assert_eq!(registers.get_af(), 0x01B0);
+        assert_eq!(registers.get_bc(), 0x0013);
+        assert_eq!(registers.get_de(), 0x00D8);
+        assert_eq!(registers.get_hl(), 0x014D);
+        assert_eq!(registers.sp, 0xFFFE);
+        assert_eq!(registers.pc, 0x0100);
+        assert!(!registers.interrupts_enabled);
+    }
+}
@@ -0,0 +1,69 @@
+//! Timings of the CPU instructions, in T-cycles.
+//!
+//! Conditional jumps, calls and returns take longer when their condition holds, hence the
+//! `taken` and `not_taken` values. CB-prefixed instructions include the fetch of the 0xCB prefix.
+
+/// Cycles taken by an instruction, in T-cycles.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub(crate) struct Cycles {
+    pub(crate) taken: u8,
+    pub(crate) not_taken: u8,
+}
+
+const fn fixed(cycles: u8) -> Cycles {
+    Cycles { taken: cycles, not_taken: cycles }
+}
+
+const fn conditional(taken: u8, not_taken: u8) -> Cycles {
+    Cycles { taken, not_taken }
+}
+
+const N: Cycles = fixed(4);
+const C8: Cycles = fixed(8);
+const C12: Cycles = fixed(12);
+const C16: Cycles = fixed(16);
+const C20: Cycles = fixed(20);
+const C24: Cycles = fixed(24);
+const JR: Cycles = conditional(12, 8);
+const JP: Cycles = conditional(16, 12);
+const CALL: Cycles = conditional(24, 12);
+const RET: Cycles = conditional(20, 8);
+/// Illegal opcodes lock the CPU up, only their fetch is accounted for.
+const ILL: Cycles = fixed(4);
+
+/// Timings of the unprefixed instructions, the 0xCB prefix being timed with the instruction it
+/// introduces.
+#[rustfmt::skip]
+pub(crate) const BASE_CYCLES: [Cycles; 256] = [
+//  x0   x1   x2   x3   x4   x5   x6   x7   x8   x9   xA   xB   xC   xD   xE   xF
+    N,   C12, C8,  C8,  N,   N,   C8,  N,   C20, C8,  C8,  C8,  N,   N,   C8,  N,   // 0x
+    N,   C12, C8,  C8,  N,   N,   C8,  N,   C12, C8,  C8,  C8,  N,   N,   C8,  N,   // 1x
+    JR,  C12, C8,  C8,  N,   N,   C8,  N,   JR,  C8,  C8,  C8,  N,   N,   C8,  N,   // 2x
+    JR,  C12, C8,  C8,  C12, C12, C12, N,   JR,  C8,  C8,  C8,  N,   N,   C8,  N,   // 3x
+    N,   N,   N,   N,   N,   N,   C8,  N,   N,   N,   N,   N,   N,   N,   C8,  N,   // 4x
+    N,   N,   N,   N,   N,   N,   C8,  N,   N,   N,   N,   N,   N,   N,   C8,  N,   // 5x
+    N,   N,   N,   N,   N,   N,   C8,  N,   N,   N,   N,   N,   N,   N,   C8,  N,   // 6x
+    C8,  C8,  C8,  C8,  C8,  C8,  N,   C8,  N,   N,   N,   N,   N,   N,   C8,  N,   // 7x
+    N,   N,   N,   N,   N,   N,   C8,  N,   N,   N,   N,   N,   N,   N,   C8,  N,   // 8x
+    N,   N,   N,   N,   N,   N,   C8,  N,   N,   N,   N,   N,   N,   N,   C8,  N,   // 9x
+    N,   N,   N,   N,   N,   N,   C8,  N,   N,   N,   N,   N,   N,   N,   C8,  N,   // Ax
+    N,   N,   N,   N,   N,   N,   C8,  N,   N,   N,   N,   N,   N,   N,   C8,  N,   // Bx
+    RET, C12, JP,  C16, CALL,C16, C8,  C16, RET, C16, JP,  N,   CALL,C24, C8,  C16, // Cx
+    RET, C12, JP,  ILL, CALL,C16, C8,  C16, RET, C16, JP,  ILL, CALL,ILL, C8,  C16, // Dx
+    C12, C12, C8,  ILL, ILL, C16, C8,  C16, C16, N,   C16, ILL, ILL, ILL, C8,  C16, // Ex
+    C12, C12, C8,  N,   ILL, C16, C8,  C16, C12, C8,  C16, N,   ILL, ILL, C8,  C16, // Fx
+];
+
+/// Timings of the CB-prefixed instructions: 16 T-cycles for the read-modify-write of (HL), 12
+/// for `BIT n,(HL)` which only reads it, and 8 for registers.
+pub(crate) const CB_CYCLES: [Cycles; 256] = {
+    let mut cycles = [C8; 256];
+    let mut opcode = 0;
+    while opcode < 256 {
+        if opcode & 0x07 == 0x06 {
+            cycles[opcode] = if opcode >= 0x40 && opcode < 0x80 { C12 } else { C16 };
+        }
+        opcode += 1;
+    }
+    cycles
+};
@@ -0,0 +1,65 @@
+//! Execution trace in the format of Gameboy Doctor, one line per instruction:
+//!
+//! ```text
+//! A:01 F:B0 B:00 C:13 D:00 E:D8 H:01 L:4D SP:FFFE PC:0100 PCMEM:00,C3,13,02
+//! ```
+//!
+//! Each line holds the registers before the instruction is executed and the 4 bytes at PC.
+//! Interrupt dispatches and the steps spent halted are not traced.
+
+use std::io::Write;
+
+use log::error;
+
+use crate::cpu::registers::Registers;
+use crate::mmu::MMU;
+
+/// State of the CPU before the instruction at PC, formatted as a trace line.
+pub(crate) fn format_state(registers: &Registers, mmu: &MMU) -> String {
+    let pc = registers.pc;
+    let pcmem: Vec<String> = (0..4).map(|offset| format!("{:02X}", mmu.peek_byte(pc.wrapping_add(offset)))).collect();
+    format!("A:{:02X} F:{:02X} B:{:02X} C:{:02X} D:{:02X} E:{:02X} H:{:02X} L:{:02X} SP:{:04X} PC:{:04X} PCMEM:{}",
+            registers.a, registers.get_af() as u8, registers.b, registers.c, registers.d, registers.e,
+            registers.h, registers.l, registers.sp, pc, pcmem.join(","))
+}
+
+/// Receiver of the state of the CPU before every instruction it executes.
+pub(crate) trait Tracer {
+    fn trace(&mut self, registers: &Registers, mmu: &MMU);
+}
+
+/// Writer of the execution trace.
+pub(crate) struct Trace {
+    /// Dropped on the first write error, stopping the trace.
+    writer: Option<Box<dyn Write>>,
+}
+
+impl Trace {
+    pub(crate) fn new(writer: Box<dyn Write>) -> Self {
+        Trace { writer: Some(writer) }
+    }
+}
+
+impl Tracer for Trace {
+    fn trace(&mut self, registers: &Registers, mmu: &MMU) {
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+        if let Err(err) = writeln!(writer, "{}", format_state(registers, mmu)) {
+            error!("Failed to write the trace, stopping it: {}", err);
+            self.writer = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_the_state_after_boot_as_gameboy_doctor() {
+        let (cpu, mmu) = crate::cpu::machine(&[0x00, 0xC3, 0x13, 0x02]);
+        assert_eq!(format_state(cpu.registers(), &mmu),
+                   "A:01 F:B0 B:00 C:13 D:00 E:D8 H:01 L:4D SP:FFFE PC:C000 PCMEM:00,C3,13,02");
+    }
+}
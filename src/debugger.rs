@@ -0,0 +1,509 @@
+//! Interactive command-line debugger.
+
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, Write};
+
+use crate::cpu::{CpuState, Disassembler, CPU};
+use crate::mmu::MMU;
+use crate::save_state::{parse_slot, StateSlots};
+use crate::signals;
+use crate::symbols::Symbols;
+use crate::watchpoints::Watchpoint;
+
+const HELP: &str = "\
+Commands, addresses and values being hexadecimal, LOC being an address, BANK:ADDR for an
+address of a given ROM bank, or a label of the symbols:
+  s, step [N]            Executes N instructions, 1 by default
+  n, next                Executes an instruction, running calls and RSTs until they return
+  c, continue            Runs until a breakpoint is hit, the CPU locks up or Ctrl-C is pressed
+  b, break LOC           Sets a breakpoint at LOC
+  d, delete LOC          Deletes the breakpoint at LOC
+  bl, breakpoints        Lists the breakpoints
+  wa, watch SPEC [log]   Sets a watchpoint, SPEC being ADDR[-END]:r|w|rw|=VALUE|change,
+                         only logging its hits with `log`
+  uw, unwatch N          Deletes the watchpoint number N
+  wl, watches            Lists the watchpoints
+  r, regs                Prints the registers
+  set REG VALUE          Sets a register: a, f, b, c, d, e, h, l, af, bc, de, hl, sp or pc
+  x, mem ADDR [LEN]      Prints LEN bytes of memory from ADDR, 16 by default
+  w, write ADDR VALUE    Writes a byte to memory
+  l, disasm [N]          Disassembles N instructions from PC, 10 by default
+  save [SLOT]            Saves the state of the machine to SLOT (0-9), 0 by default
+  load [SLOT]            Loads the state of the machine from SLOT (0-9), 0 by default
+  h, help                Prints this help
+  q, quit                Quits the emulator
+An empty line repeats the last command.";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Command {
+    Step(u32),
+    Next,
+    Continue,
+    Break(String),
+    Delete(String),
+    Breakpoints,
+    Watch(Watchpoint),
+    Unwatch(usize),
+    Watches,
+    Registers,
+    Set(String, u16),
+    Memory(u16, u16),
+    Write(u16, u8),
+    Disassemble(u16),
+    SaveState(u8),
+    LoadState(u8),
+    Help,
+    Quit,
+}
+
+/// Parses a hexadecimal number, optionally prefixed with `0x` or `$`.
+pub(crate) fn parse_number(value: &str) -> Result<u16, String> {
+    let digits = value.trim_start_matches("0x").trim_start_matches('$');
+    u16::from_str_radix(digits, 16).map_err(|_| format!("Invalid hexadecimal number: {}", value))
+}
+
+/// Parses a hexadecimal ROM bank number, as in symbol files.
+pub(crate) fn parse_bank(value: &str) -> Result<usize, String> {
+    let digits = value.trim_start_matches("0x").trim_start_matches('$');
+    usize::from_str_radix(digits, 16).map_err(|_| format!("Invalid bank: {}", value))
+}
+
+fn parse_byte(value: &str) -> Result<u8, String> {
+    u8::try_from(parse_number(value)?).map_err(|_| format!("Not a byte: {}", value))
+}
+
+impl Command {
+    fn parse(line: &str) -> Result<Self, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let argument = |index: usize| words.get(index).copied().ok_or_else(|| String::from("Missing argument, see `help`"));
+        match words[0] {
+            "s" | "step" => match words.get(1) {
+                Some(count) => count.parse().map(Command::Step).map_err(|_| format!("Invalid count: {}", count)),
+                None => Ok(Command::Step(1)),
+            },
+            "n" | "next" => Ok(Command::Next),
+            "c" | "continue" => Ok(Command::Continue),
+            "b" | "break" => Ok(Command::Break(argument(1)?.to_string())),
+            "d" | "delete" => Ok(Command::Delete(argument(1)?.to_string())),
+            "bl" | "breakpoints" => Ok(Command::Breakpoints),
+            "wa" | "watch" => Ok(Command::Watch(Watchpoint::parse(argument(1)?, words.get(2) == Some(&"log"))?)),
+            "uw" | "unwatch" => argument(1)?.parse().map(Command::Unwatch).map_err(|_| format!("Invalid watchpoint number: {}", words[1])),
+            "wl" | "watches" => Ok(Command::Watches),
+            "r" | "regs" => Ok(Command::Registers),
+            "set" => Ok(Command::Set(argument(1)?.to_lowercase(), parse_number(argument(2)?)?)),
+            "x" | "mem" => Ok(Command::Memory(parse_number(argument(1)?)?, words.get(2).map_or(Ok(16), |len| parse_number(len))?)),
+            "w" | "write" => Ok(Command::Write(parse_number(argument(1)?)?, parse_byte(argument(2)?)?)),
+            "l" | "disasm" => match words.get(1) {
+                Some(count) => count.parse().map(Command::Disassemble).map_err(|_| format!("Invalid count: {}", count)),
+                None => Ok(Command::Disassemble(10)),
+            },
+            "save" => Ok(Command::SaveState(words.get(1).map_or(Ok(0), |slot| parse_slot(slot))?)),
+            "load" => Ok(Command::LoadState(words.get(1).map_or(Ok(0), |slot| parse_slot(slot))?)),
+            "h" | "help" => Ok(Command::Help),
+            "q" | "quit" => Ok(Command::Quit),
+            command => Err(format!("Unknown command: {}, see `help`", command)),
+        }
+    }
+}
+
+/// Breakpoint on an address, restricted to a ROM bank for addresses in 0x4000-0x7FFF.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Breakpoint {
+    address: u16,
+    bank: Option<usize>,
+}
+
+impl Breakpoint {
+    fn new(address: u16, bank: Option<usize>) -> Self {
+        Breakpoint {
+            address,
+            bank: bank.filter(|_| (0x4000..=0x7FFF).contains(&address)),
+        }
+    }
+
+    fn is_hit(&self, pc: u16, mmu: &MMU) -> bool {
+        self.address == pc && self.bank.is_none_or(|bank| bank == mmu.cartridge.current_rom_bank())
+    }
+}
+
+/// Debugger driving the emulation from commands read on stdin.
+pub(crate) struct Debugger {
+    breakpoints: BTreeSet<Breakpoint>,
+    last_command: Option<Command>,
+    disassembler: Disassembler,
+    state_slots: Option<StateSlots>,
+}
+
+impl Debugger {
+    pub(crate) fn new() -> Self {
+        Debugger {
+            breakpoints: BTreeSet::new(),
+            last_command: None,
+            disassembler: Disassembler::new(),
+            state_slots: None,
+        }
+    }
+
+    /// Enables the `save` and `load` commands, keeping the states in `state_slots`.
+    pub(crate) fn with_state_slots(self, state_slots: StateSlots) -> Self {
+        Debugger {
+            state_slots: Some(state_slots),
+            ..self
+        }
+    }
+
+    /// Uses the labels of `symbols` in the disassembly and as breakpoint locations.
+    pub(crate) fn with_symbols(self, symbols: Option<Symbols>) -> Self {
+        Debugger {
+            disassembler: self.disassembler.with_symbols(symbols),
+            ..self
+        }
+    }
+
+    /// Runs the debugger until it is quit or stdin is closed.
+    pub(crate) fn run(&mut self, cpu: &mut CPU, mmu: &mut MMU) {
+        println!("Type `help` for the list of commands.");
+        print_registers(cpu);
+        self.print_disassembly(cpu, mmu, 1);
+        let stdin = io::stdin();
+        let mut lines = stdin.lock().lines();
+        loop {
+            print!("> ");
+            io::stdout().flush().ok();
+            let Some(Ok(line)) = lines.next() else {
+                return;
+            };
+            let command = if line.trim().is_empty() {
+                match &self.last_command {
+                    Some(command) => command.clone(),
+                    None => continue,
+                }
+            } else {
+                match Command::parse(&line) {
+                    Ok(command) => command,
+                    Err(err) => {
+                        println!("{}", err);
+                        continue;
+                    }
+                }
+            };
+            if let Command::Quit = command {
+                return;
+            }
+            self.execute(&command, cpu, mmu);
+            self.last_command = Some(command);
+        }
+    }
+
+    fn execute(&mut self, command: &Command, cpu: &mut CPU, mmu: &mut MMU) {
+        match command {
+            Command::Step(count) => {
+                signals::take_interrupt();
+                for _ in 0..*count {
+                    if self.step(cpu, mmu) || self.at_breakpoint(cpu, mmu) || self.interrupted(cpu) {
+                        break;
+                    }
+                }
+                self.print_stop(cpu, mmu);
+            }
+            Command::Next => {
+                let pc = cpu.registers().pc;
+                match cpu.instruction_at(pc, mmu) {
+                    Some(instruction) if instruction.mnemonic.starts_with("CALL") || instruction.mnemonic.starts_with("RST") => {
+                        let sp = cpu.registers().sp;
+                        let return_address = pc.wrapping_add(instruction.bytes as u16);
+                        if !self.step(cpu, mmu) {
+                            // The stack pointer tells a return from a recursive call to the same address.
+                            self.run_until(cpu, mmu, |cpu| cpu.registers().pc == return_address && cpu.registers().sp >= sp);
+                        }
+                    }
+                    _ => {
+                        self.step(cpu, mmu);
+                    }
+                }
+                self.print_stop(cpu, mmu);
+            }
+            Command::Continue => {
+                if !self.step(cpu, mmu) {
+                    self.run_until(cpu, mmu, |_| false);
+                }
+                self.print_stop(cpu, mmu);
+            }
+            Command::Break(location) => match self.resolve(location) {
+                Ok(breakpoint) => {
+                    self.breakpoints.insert(breakpoint);
+                    println!("Breakpoint set at {}", self.describe_breakpoint(&breakpoint));
+                }
+                Err(err) => println!("{}", err),
+            },
+            Command::Delete(location) => match self.resolve(location) {
+                Ok(breakpoint) if self.breakpoints.remove(&breakpoint) => {
+                    println!("Breakpoint deleted at {}", self.describe_breakpoint(&breakpoint));
+                }
+                Ok(breakpoint) => println!("No breakpoint at {}", self.describe_breakpoint(&breakpoint)),
+                Err(err) => println!("{}", err),
+            },
+            Command::Breakpoints => {
+                if self.breakpoints.is_empty() {
+                    println!("No breakpoints");
+                }
+                for breakpoint in &self.breakpoints {
+                    println!("{}", self.describe_breakpoint(breakpoint));
+                }
+            }
+            Command::Watch(watchpoint) => {
+                mmu.add_watchpoint(*watchpoint);
+                println!("Watchpoint {} set on {}", mmu.watchpoints().len() - 1, watchpoint);
+            }
+            Command::Unwatch(index) => match mmu.remove_watchpoint(*index) {
+                Some(watchpoint) => println!("Watchpoint {} deleted on {}", index, watchpoint),
+                None => println!("No watchpoint number {}", index),
+            },
+            Command::Watches => {
+                if mmu.watchpoints().is_empty() {
+                    println!("No watchpoints");
+                }
+                for (index, watchpoint) in mmu.watchpoints().iter().enumerate() {
+                    println!("{}: {}", index, watchpoint);
+                }
+            }
+            Command::Registers => print_registers(cpu),
+            Command::Set(register, value) => match set_register(cpu, register, *value) {
+                Ok(()) => print_registers(cpu),
+                Err(err) => println!("{}", err),
+            },
+            Command::Memory(address, length) => print_memory(*address, *length, |address| mmu.peek_byte(address)),
+            Command::Write(address, value) => mmu.poke_byte(*address, *value),
+            Command::Disassemble(count) => self.print_disassembly(cpu, mmu, *count),
+            Command::SaveState(slot) => match &self.state_slots {
+                Some(state_slots) => match state_slots.save(*slot, cpu, mmu) {
+                    Ok(path) => println!("State saved to {}", path.display()),
+                    Err(err) => println!("{}", err),
+                },
+                None => println!("Save states are not available"),
+            },
+            Command::LoadState(slot) => match &self.state_slots {
+                Some(state_slots) => match state_slots.load(*slot, cpu, mmu) {
+                    Ok(path) => {
+                        println!("State loaded from {}", path.display());
+                        self.print_stop(cpu, mmu);
+                    }
+                    Err(err) => println!("{}", err),
+                },
+                None => println!("Save states are not available"),
+            },
+            Command::Help => println!("{}", HELP),
+            Command::Quit => (),
+        }
+    }
+
+    /// Resolves a breakpoint location: a label, `<bank>:<addr>` or `<addr>`.
+    fn resolve(&self, location: &str) -> Result<Breakpoint, String> {
+        if let Some((bank, address)) = self.disassembler.symbols().and_then(|symbols| symbols.address(location)) {
+            return Ok(Breakpoint::new(address, Some(bank)));
+        }
+        match location.split_once(':') {
+            Some((bank, address)) => {
+                Ok(Breakpoint::new(parse_number(address)?, Some(parse_bank(bank)?)))
+            }
+            None => Ok(Breakpoint::new(parse_number(location)?, None)),
+        }
+    }
+
+    fn describe_breakpoint(&self, breakpoint: &Breakpoint) -> String {
+        let label = self.disassembler.label(breakpoint.address, breakpoint.bank.unwrap_or(1));
+        match (breakpoint.bank, label) {
+            (Some(bank), Some(label)) => format!("{:02X}:{:04X} ({})", bank, breakpoint.address, label),
+            (Some(bank), None) => format!("{:02X}:{:04X}", bank, breakpoint.address),
+            (None, Some(label)) => format!("{:04X} ({})", breakpoint.address, label),
+            (None, None) => format!("{:04X}", breakpoint.address),
+        }
+    }
+
+    /// `address`, followed by its label if it has one in the ROM bank currently mapped.
+    fn describe_address(&self, address: u16, mmu: &MMU) -> String {
+        match self.disassembler.label(address, mmu.cartridge.current_rom_bank()) {
+            Some(label) => format!("{:04X} ({})", address, label),
+            None => format!("{:04X}", address),
+        }
+    }
+
+    fn at_breakpoint(&self, cpu: &CPU, mmu: &MMU) -> bool {
+        let pc = cpu.registers().pc;
+        self.breakpoints.iter().any(|breakpoint| breakpoint.is_hit(pc, mmu))
+    }
+
+    /// Runs a step of the CPU, printing the watchpoints it triggered.
+    ///
+    /// Returns whether a watchpoint stopped the execution.
+    fn step(&self, cpu: &mut CPU, mmu: &mut MMU) -> bool {
+        let pc = cpu.registers().pc;
+        cpu.step(mmu);
+        let mut stop = false;
+        for hit in mmu.take_watchpoint_hits() {
+            println!("Watchpoint hit by PC={}: {}", self.describe_address(pc, mmu), hit);
+            stop |= !hit.watchpoint.log_only;
+        }
+        stop
+    }
+
+    /// Whether the execution must stop as the CPU locked up or Ctrl-C was pressed.
+    fn interrupted(&self, cpu: &CPU) -> bool {
+        if cpu.registers().cpu_state == CpuState::Locked {
+            println!("CPU locked up by an illegal opcode");
+            return true;
+        }
+        if signals::take_interrupt() {
+            println!("Interrupted");
+            return true;
+        }
+        false
+    }
+
+    /// Runs until `stop` holds, a breakpoint or watchpoint is hit, the CPU locks up or Ctrl-C is
+    /// pressed.
+    fn run_until(&self, cpu: &mut CPU, mmu: &mut MMU, stop: impl Fn(&CPU) -> bool) {
+        // Ctrl-C pressed at the prompt must not stop the execution right away.
+        signals::take_interrupt();
+        while !stop(cpu) && !self.at_breakpoint(cpu, mmu) && !self.interrupted(cpu) {
+            if self.step(cpu, mmu) {
+                return;
+            }
+        }
+    }
+
+    fn print_disassembly(&self, cpu: &CPU, mmu: &MMU, count: u16) {
+        let mut address = cpu.registers().pc;
+        for _ in 0..count {
+            let marker = if address == cpu.registers().pc { "=>" } else { "  " };
+            let line = self.disassembler.disassemble(address, mmu.cartridge.current_rom_bank(), |address| mmu.peek_byte(address));
+            if let Some(label) = &line.label {
+                println!("{}:", label);
+            }
+            let bytes: Vec<String> = line.bytes.iter().map(|byte| format!("{:02X}", byte)).collect();
+            println!("{} {:04X}: {:<9} {}", marker, address, bytes.join(" "), line.text);
+            address = address.wrapping_add(line.bytes.len() as u16);
+        }
+    }
+
+    fn print_stop(&self, cpu: &CPU, mmu: &MMU) {
+        if self.at_breakpoint(cpu, mmu) {
+            println!("Breakpoint hit at {}", self.describe_address(cpu.registers().pc, mmu));
+        }
+        print_registers(cpu);
+        self.print_disassembly(cpu, mmu, 1);
+    }
+}
+
+fn set_register(cpu: &mut CPU, register: &str, value: u16) -> Result<(), String> {
+    let registers = cpu.registers_mut();
+    let byte = || u8::try_from(value).map_err(|_| format!("Not a byte: {:X}", value));
+    match register {
+        "a" => registers.a = byte()?,
+        "f" => registers.set_af((registers.a as u16) << 8 | byte()? as u16),
+        "b" => registers.b = byte()?,
+        "c" => registers.c = byte()?,
+        "d" => registers.d = byte()?,
+        "e" => registers.e = byte()?,
+        "h" => registers.h = byte()?,
+        "l" => registers.l = byte()?,
+        "af" => registers.set_af(value),
+        "bc" => registers.set_bc(value),
+        "de" => registers.set_de(value),
+        "hl" => registers.set_hl(value),
+        "sp" => registers.sp = value,
+        "pc" => registers.pc = value,
+        _ => return Err(format!("Unknown register: {}", register)),
+    }
+    Ok(())
+}
+
+fn print_registers(cpu: &CPU) {
+    let registers = cpu.registers();
+    let flag = |set: bool, name: char| if set { name } else { '-' };
+    println!(
+        "AF={:04X} BC={:04X} DE={:04X} HL={:04X} SP={:04X} PC={:04X} {}{}{}{} IME={}",
+        registers.get_af(), registers.get_bc(), registers.get_de(), registers.get_hl(), registers.sp, registers.pc,
+        flag(registers.f.z, 'Z'), flag(registers.f.n, 'N'), flag(registers.f.h, 'H'), flag(registers.f.c, 'C'),
+        registers.interrupts_enabled as u8,
+    );
+}
+
+/// Prints `length` bytes from `address`, reading memory with `read`.
+pub(crate) fn print_memory(address: u16, length: u16, read: impl Fn(u16) -> u8) {
+    for line_start in (0..length).step_by(16) {
+        let line_address = address.wrapping_add(line_start);
+        let bytes: Vec<String> = (line_start..length.min(line_start.saturating_add(16)))
+            .map(|offset| format!("{:02X}", read(address.wrapping_add(offset))))
+            .collect();
+        println!("{:04X}: {}", line_address, bytes.join(" "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::machine;
+
+    #[test]
+    fn parses_commands_and_their_hexadecimal_arguments() {
+        assert_eq!(Command::parse("s").unwrap(), Command::Step(1));
+        assert_eq!(Command::parse("step 10").unwrap(), Command::Step(10));
+        assert_eq!(Command::parse("n").unwrap(), Command::Next);
+        assert_eq!(Command::parse("b 0x0150").unwrap(), Command::Break(String::from("0x0150")));
+        assert_eq!(Command::parse("x $FF40 4").unwrap(), Command::Memory(0xFF40, 4));
+        assert_eq!(Command::parse("x C000").unwrap(), Command::Memory(0xC000, 16));
+        assert_eq!(Command::parse("w C000 3C").unwrap(), Command::Write(0xC000, 0x3C));
+        assert_eq!(Command::parse("set HL D000").unwrap(), Command::Set(String::from("hl"), 0xD000));
+        assert!(Command::parse("w C000 100").is_err());
+        assert!(Command::parse("x G000").is_err());
+        assert!(Command::parse("b").is_err());
+        assert!(Command::parse("jump").is_err());
+    }
+
+    #[test]
+    fn resolves_breakpoints_on_addresses_banks_and_labels() {
+        let symbols = Symbols::parse("00:0150 Main\n02:4100 Bank2Routine\n00:C000 wBuffer\n").unwrap();
+        let debugger = Debugger::new().with_symbols(Some(symbols));
+        assert_eq!(debugger.resolve("0150").unwrap(), Breakpoint { address: 0x0150, bank: None });
+        assert_eq!(debugger.resolve("1F:4100").unwrap(), Breakpoint { address: 0x4100, bank: Some(0x1F) });
+        // Only the switchable ROM area is banked.
+        assert_eq!(debugger.resolve("2:C000").unwrap(), Breakpoint { address: 0xC000, bank: None });
+        assert_eq!(debugger.resolve("Main").unwrap(), Breakpoint { address: 0x0150, bank: None });
+        assert_eq!(debugger.resolve("Bank2Routine").unwrap(), Breakpoint { address: 0x4100, bank: Some(2) });
+        assert!(debugger.resolve("Missing").is_err());
+    }
+
+    #[test]
+    fn banked_breakpoints_are_only_hit_in_their_bank() {
+        // Without a cartridge, bank 1 is mapped at 0x4000-0x7FFF.
+        let mmu = MMU::new();
+        assert!(Breakpoint::new(0x4100, Some(1)).is_hit(0x4100, &mmu));
+        assert!(!Breakpoint::new(0x4100, Some(2)).is_hit(0x4100, &mmu));
+        assert!(Breakpoint::new(0x4100, None).is_hit(0x4100, &mmu));
+        assert!(!Breakpoint::new(0x4100, Some(1)).is_hit(0x4101, &mmu));
+    }
+
+    #[test]
+    fn next_steps_over_calls_to_the_return_address() {
+        let mut program = [0x00; 0x13];
+        program[..3].copy_from_slice(&[0xCD, 0x10, 0xC0]); // CALL 0xC010
+        program[0x10..].copy_from_slice(&[
+            0x3C, // INC A
+            0x3C, // INC A
+            0xC9, // RET
+        ]);
+        let (mut cpu, mut mmu) = machine(&program);
+        cpu.registers_mut().a = 0;
+        let sp = cpu.registers().sp;
+
+        let mut debugger = Debugger::new();
+        debugger.execute(&Command::Next, &mut cpu, &mut mmu);
+        assert_eq!(cpu.registers().pc, 0xC003);
+        assert_eq!(cpu.registers().sp, sp);
+        assert_eq!(cpu.registers().a, 2);
+
+        debugger.execute(&Command::Next, &mut cpu, &mut mmu);
+        assert_eq!(cpu.registers().pc, 0xC004);
+    }
+}
@@ -0,0 +1,73 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::joypad::Button;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum InputAction {
+    Press,
+    Release,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct InputEvent {
+    pub(crate) frame: u32,
+    pub(crate) action: InputAction,
+    pub(crate) button: Button,
+}
+
+/// Scripted joypad input, replayed frame by frame.
+///
+/// Replay files hold one event per line, as `<frame> <press|release> <button>`, e.g.
+/// `120 press start` to press Start before running the 120th frame, frames being numbered
+/// from 1. Empty lines and lines starting with `#` are ignored.
+pub(crate) struct InputReplay {
+    events: Vec<InputEvent>,
+    next: usize,
+}
+
+impl InputReplay {
+    pub(crate) fn from_path(path: &Path) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    pub(crate) fn parse(content: &str) -> Result<Self, String> {
+        let mut events = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let event = Self::parse_event(line).map_err(|err| format!("line {}: {}", number + 1, err))?;
+            events.push(event);
+        }
+        // Keep the file order for events of a same frame.
+        events.sort_by_key(|event| event.frame);
+        Ok(InputReplay { events, next: 0 })
+    }
+
+    fn parse_event(line: &str) -> Result<InputEvent, String> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [frame, action, button] = fields[..] else {
+            return Err(format!("expected `<frame> <press|release> <button>`, got `{}`", line));
+        };
+        let frame = frame.parse().map_err(|_| format!("invalid frame number: {}", frame))?;
+        let action = match action.to_ascii_lowercase().as_str() {
+            "press" => InputAction::Press,
+            "release" => InputAction::Release,
+            _ => return Err(format!("unknown action: {}", action)),
+        };
+        Ok(InputEvent { frame, action, button: button.parse()? })
+    }
+
+    /// Returns the events due before running `frame` that have not been replayed yet.
+    pub(crate) fn events_for_frame(&mut self, frame: u32) -> &[InputEvent] {
+        let start = self.next;
+        while self.next < self.events.len() && self.events[self.next].frame <= frame {
+            self.next += 1;
+        }
+        &self.events[start..self.next]
+    }
+}
@@ -0,0 +1,106 @@
+use crate::save_state::{Snapshot, StateReader, StateWriter};
+
+/// Interrupt sources, listed by decreasing priority.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Interrupt {
+    VBlank,
+    LcdStat,
+    Timer,
+    Serial,
+    Joypad,
+}
+
+impl Interrupt {
+    pub(crate) const ALL: [Interrupt; 5] = [
+        Interrupt::VBlank,
+        Interrupt::LcdStat,
+        Interrupt::Timer,
+        Interrupt::Serial,
+        Interrupt::Joypad,
+    ];
+
+    pub(crate) fn mask(&self) -> u8 {
+        match self {
+            Interrupt::VBlank => 0b0000_0001,
+            Interrupt::LcdStat => 0b0000_0010,
+            Interrupt::Timer => 0b0000_0100,
+            Interrupt::Serial => 0b0000_1000,
+            Interrupt::Joypad => 0b0001_0000,
+        }
+    }
+
+    pub(crate) fn vector(&self) -> u16 {
+        match self {
+            Interrupt::VBlank => 0x0040,
+            Interrupt::LcdStat => 0x0048,
+            Interrupt::Timer => 0x0050,
+            Interrupt::Serial => 0x0058,
+            Interrupt::Joypad => 0x0060,
+        }
+    }
+}
+
+/// Interrupt controller holding the IF (0xFF0F) and IE (0xFFFF) registers.
+pub(crate) struct Interrupts {
+    flag: u8,
+    enable: u8,
+}
+
+impl Interrupts {
+    pub(crate) const IF_ADDRESS: u16 = 0xFF0F;
+    pub(crate) const IE_ADDRESS: u16 = 0xFFFF;
+
+    /// Only the lower five bits of IF and IE are wired to interrupt sources.
+    const SOURCES_MASK: u8 = 0b0001_1111;
+
+    pub(crate) fn new() -> Self {
+        Interrupts {
+            flag: 0,
+            enable: 0,
+        }
+    }
+
+    pub(crate) fn request(&mut self, interrupt: Interrupt) {
+        self.flag |= interrupt.mask();
+    }
+
+    pub(crate) fn acknowledge(&mut self, interrupt: Interrupt) {
+        self.flag &= !interrupt.mask();
+    }
+
+    /// Returns the highest priority interrupt that is both requested and enabled.
+    pub(crate) fn pending(&self) -> Option<Interrupt> {
+        let pending = self.flag & self.enable & Self::SOURCES_MASK;
+        Interrupt::ALL.into_iter().find(|interrupt| pending & interrupt.mask() != 0)
+    }
+
+    pub(crate) fn read_flag(&self) -> u8 {
+        // Unused upper bits of IF always read back as 1.
+        self.flag | !Self::SOURCES_MASK
+    }
+
+    pub(crate) fn write_flag(&mut self, value: u8) {
+        self.flag = value & Self::SOURCES_MASK;
+    }
+
+    pub(crate) fn read_enable(&self) -> u8 {
+        self.enable
+    }
+
+    pub(crate) fn write_enable(&mut self, value: u8) {
+        self.enable = value;
+    }
+}
+
+impl Snapshot for Interrupts {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.flag);
+        state.write_u8(self.enable);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.flag = state.read_u8()?;
+        self.enable = state.read_u8()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+use std::str::FromStr;
+
+use crate::interrupts::{Interrupt, Interrupts};
+use crate::save_state::{Snapshot, StateReader, StateWriter};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Button {
+    Right,
+    Left,
+    Up,
+    Down,
+    A,
+    B,
+    Select,
+    Start,
+}
+
+impl Button {
+    /// Bit of the button in the joypad state: direction keys in the lower nibble, action buttons
+    /// in the upper one, each nibble being laid out as the P1 input lines.
+    fn mask(&self) -> u8 {
+        match self {
+            Button::Right => 0b0000_0001,
+            Button::Left => 0b0000_0010,
+            Button::Up => 0b0000_0100,
+            Button::Down => 0b0000_1000,
+            Button::A => 0b0001_0000,
+            Button::B => 0b0010_0000,
+            Button::Select => 0b0100_0000,
+            Button::Start => 0b1000_0000,
+        }
+    }
+}
+
+impl FromStr for Button {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "right" => Ok(Button::Right),
+            "left" => Ok(Button::Left),
+            "up" => Ok(Button::Up),
+            "down" => Ok(Button::Down),
+            "a" => Ok(Button::A),
+            "b" => Ok(Button::B),
+            "select" => Ok(Button::Select),
+            "start" => Ok(Button::Start),
+            _ => Err(format!("Unknown button: {}", name)),
+        }
+    }
+}
+
+/// Joypad register P1 (0xFF00).
+///
+/// Bits 4 and 5 select the direction keys and the action buttons respectively when written
+/// with 0, bits 0 to 3 reading the selected input lines, 0 meaning pressed.
+pub(crate) struct Joypad {
+    select: u8,
+    pressed: u8,
+}
+
+impl Joypad {
+    pub(crate) const P1_ADDRESS: u16 = 0xFF00;
+
+    const SELECT_DIRECTIONS: u8 = 0b0001_0000;
+    const SELECT_ACTIONS: u8 = 0b0010_0000;
+
+    pub(crate) fn new() -> Self {
+        Joypad {
+            select: Self::SELECT_DIRECTIONS | Self::SELECT_ACTIONS,
+            pressed: 0,
+        }
+    }
+
+    pub(crate) fn read_byte(&self) -> u8 {
+        0b1100_0000 | self.select | (!self.lines() & 0x0F)
+    }
+
+    pub(crate) fn write_byte(&mut self, value: u8, interrupts: &mut Interrupts) {
+        let lines = self.lines();
+        self.select = value & (Self::SELECT_DIRECTIONS | Self::SELECT_ACTIONS);
+        self.update_interrupt(lines, interrupts);
+    }
+
+    pub(crate) fn press(&mut self, button: Button, interrupts: &mut Interrupts) {
+        let lines = self.lines();
+        self.pressed |= button.mask();
+        self.update_interrupt(lines, interrupts);
+    }
+
+    pub(crate) fn release(&mut self, button: Button) {
+        self.pressed &= !button.mask();
+    }
+
+    /// Selected input lines, 1 meaning pressed.
+    fn lines(&self) -> u8 {
+        let mut lines = 0;
+        if self.select & Self::SELECT_DIRECTIONS == 0 {
+            lines |= self.pressed & 0x0F;
+        }
+        if self.select & Self::SELECT_ACTIONS == 0 {
+            lines |= self.pressed >> 4;
+        }
+        lines
+    }
+
+    /// Requests the joypad interrupt when an input line goes from high to low.
+    fn update_interrupt(&self, previous_lines: u8, interrupts: &mut Interrupts) {
+        if self.lines() & !previous_lines != 0 {
+            interrupts.request(Interrupt::Joypad);
+        }
+    }
+}
+
+impl Snapshot for Joypad {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.select);
+        state.write_u8(self.pressed);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.select = state.read_u8()?;
+        self.pressed = state.read_u8()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn joypad_requested(interrupts: &Interrupts) -> bool {
+        interrupts.read_flag() & Interrupt::Joypad.mask() != 0
+    }
+
+    /// Joypad with Right and A pressed, reading P1 after writing `select` to it.
+    fn read_with_select(select: u8) -> u8 {
+        let mut interrupts = Interrupts::new();
+        let mut joypad = Joypad::new();
+        joypad.press(Button::Right, &mut interrupts);
+        joypad.press(Button::A, &mut interrupts);
+        joypad.write_byte(select, &mut interrupts);
+        joypad.read_byte()
+    }
+
+    #[test]
+    fn p1_reads_the_lines_of_the_selected_buttons() {
+        // P14 low: direction keys.
+        assert_eq!(read_with_select(0b0010_0000), 0b1110_1110);
+        // P15 low: action buttons.
+        assert_eq!(read_with_select(0b0001_0000), 0b1101_1110);
+        // Both low: the lines of both groups are combined.
+        assert_eq!(read_with_select(0b0000_0000), 0b1100_1110);
+        // Both high: nothing selected, every line reads released.
+        assert_eq!(read_with_select(0b0011_0000), 0b1111_1111);
+    }
+
+    #[test]
+    fn interrupt_is_requested_when_a_selected_line_goes_low() {
+        let mut interrupts = Interrupts::new();
+        let mut joypad = Joypad::new();
+        joypad.write_byte(0b0010_0000, &mut interrupts);
+
+        joypad.press(Button::Start, &mut interrupts);
+        assert!(!joypad_requested(&interrupts));
+
+        joypad.press(Button::Down, &mut interrupts);
+        assert!(joypad_requested(&interrupts));
+    }
+
+    #[test]
+    fn interrupt_is_not_requested_again_for_a_line_already_low() {
+        let mut interrupts = Interrupts::new();
+        let mut joypad = Joypad::new();
+        joypad.write_byte(0b0010_0000, &mut interrupts);
+        joypad.press(Button::Down, &mut interrupts);
+        interrupts.acknowledge(Interrupt::Joypad);
+
+        joypad.press(Button::Down, &mut interrupts);
+        joypad.release(Button::Down);
+        assert!(!joypad_requested(&interrupts));
+    }
+
+    #[test]
+    fn selecting_a_group_with_a_pressed_button_requests_the_interrupt() {
+        let mut interrupts = Interrupts::new();
+        let mut joypad = Joypad::new();
+        joypad.press(Button::B, &mut interrupts);
+        assert!(!joypad_requested(&interrupts));
+
+        joypad.write_byte(0b0001_0000, &mut interrupts);
+        assert!(joypad_requested(&interrupts));
+    }
+}
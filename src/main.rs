@@ -1,34 +1,247 @@
+#![allow(clippy::upper_case_acronyms)]
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
 use std::process;
+use std::thread;
+use std::time::Instant;
 
-use clap::Parser;
-use log::{debug, error};
+use clap::{Parser, Subcommand};
+use log::{debug, error, info, warn};
 
-use crate::cpu::CPU;
+use crate::apu::{OUTPUT_CHANNELS, SAMPLE_RATE};
+use crate::clock::{cycles_to_time, CYCLES_PER_FRAME};
+use crate::cpu::{Trace, CPU};
+use crate::debugger::Debugger;
+use crate::input_replay::{InputAction, InputReplay};
+use crate::mbc::RtcClock;
 use crate::mmu::MMU;
 use crate::rom::Rom;
+use crate::save::SaveFile;
+use crate::save_state::StateSlots;
+use crate::screenshot::ImageFormat;
+use crate::serial::{Link, LinkAddress, OutputEndpoint};
+use crate::symbols::Symbols;
+use crate::watchpoints::Watchpoint;
+use crate::wav::WavWriter;
 
+mod clock;
 mod mmu;
+mod mooneye;
 mod types;
 mod cartridge;
+mod checksum;
+mod debugger;
+mod mbc;
 mod rom;
+mod save;
+mod signals;
+mod save_state;
+mod screenshot;
+mod serial;
+mod symbols;
+mod wav;
+mod joypad;
+mod input_replay;
 mod cpu;
+mod apu;
+mod interrupts;
+mod ppu;
+mod timer;
+mod trace_diff;
+mod watchpoints;
+
+#[cfg(test)]
+mod test_roms;
 
 /// Retrieve the value of the `CARGO_PKG_VERSION` environment variable.
-const OPT_SETUP: &'static str = env!("CARGO_PKG_VERSION");
+const OPT_SETUP: &str = env!("CARGO_PKG_VERSION");
 
 /// Retrieve the value of the `CARGO_PKG_AUTHORS` environment variable.
-const AUTHOR_SETUP: &'static str = env!("CARGO_PKG_AUTHORS");
+const AUTHOR_SETUP: &str = env!("CARGO_PKG_AUTHORS");
 
 /// Retrieve the value of the `CARGO_PKG_DESCRIPTION` environment variable.
-const ABOUT_SETUP: &'static str = env!("CARGO_PKG_DESCRIPTION");
+const ABOUT_SETUP: &str = env!("CARGO_PKG_DESCRIPTION");
+
+/// Number of frames between two flushes of the save file, about 5 seconds.
+const SAVE_INTERVAL_FRAMES: u32 = 300;
 
 /// Command-line options
 #[derive(Parser)]
-#[clap(version = OPT_SETUP, author = AUTHOR_SETUP, about = ABOUT_SETUP)]
+#[clap(version = OPT_SETUP, author = AUTHOR_SETUP, about = ABOUT_SETUP, subcommand_negates_reqs = true)]
 struct Opt {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     /// The command line argument for specifying the path to a ROM file.
     #[clap(short = 'r', long = "rom", required = true)]
-    rom: String,
+    rom: Option<String>,
+
+    /// Runs as fast as possible without display for the number of frames given by `--frames`.
+    #[clap(long = "headless", requires = "frames")]
+    headless: bool,
+
+    /// Runs the ROM under an interactive debugger reading commands on stdin.
+    #[clap(long = "debug", conflicts_with = "headless")]
+    debug: bool,
+
+    /// Path of the symbol file of the ROM, labelling the debugger and the debug log, `<rom>.sym`
+    /// by default.
+    #[clap(long = "sym", value_name = "PATH")]
+    sym: Option<PathBuf>,
+
+    /// Path of a file every instruction executed is traced to in the Gameboy Doctor format, `-`
+    /// for stdout.
+    #[clap(long = "trace", value_name = "PATH")]
+    trace: Option<PathBuf>,
+
+    /// Reads LY (FF44) as 0x90, as Gameboy Doctor expects when comparing traces.
+    #[clap(long = "stub-ly")]
+    stub_ly: bool,
+
+    /// Loads the save state of the given slot (0-9), `<rom>.ss<slot>`, before running the ROM.
+    #[clap(long = "load-state", value_name = "SLOT", value_parser = save_state::parse_slot)]
+    load_state: Option<u8>,
+
+    /// Saves the state of the machine to the given slot (0-9) when the run ends: after the frames
+    /// of a headless run, when Ctrl-C is pressed or when the debugger is quit.
+    #[clap(long = "save-state", value_name = "SLOT", value_parser = save_state::parse_slot)]
+    save_state: Option<u8>,
+
+    /// Watchpoint on `<addr>[-<end>]:<r|w|rw|=<value>|change>`, logging its hits or stopping the
+    /// debugger. May be repeated.
+    #[clap(long = "watch", value_name = "SPEC")]
+    watch: Vec<String>,
+
+    /// Number of frames to run in headless mode.
+    #[clap(long = "frames", value_name = "N", requires = "headless")]
+    frames: Option<u32>,
+
+    /// Path of the PNG (.png) or PPM (.ppm) file the last frame is dumped to in headless mode.
+    #[clap(long = "screenshot", value_name = "PATH", requires = "headless")]
+    screenshot: Option<PathBuf>,
+
+    /// Dumps every Nth frame instead of the last one, numbering the screenshot files.
+    #[clap(long = "screenshot-every", value_name = "N", requires = "screenshot")]
+    screenshot_every: Option<u32>,
+
+    /// Path of a WAV file the audio of the run is written to.
+    #[clap(long = "wav-out", value_name = "PATH")]
+    wav_out: Option<PathBuf>,
+
+    /// Path of an input replay file driving the joypad, one `<frame> <press|release> <button>` per line.
+    #[clap(long = "input-replay", value_name = "PATH")]
+    input_replay: Option<PathBuf>,
+
+    /// Path of a file the bytes sent through the serial port are written to, `-` for stdout.
+    #[clap(long = "serial-out", value_name = "PATH", conflicts_with_all = ["link_listen", "link_connect"])]
+    serial_out: Option<PathBuf>,
+
+    /// Waits for another instance to plug its link cable in, on `<host>:<port>` or `unix:<path>`.
+    #[clap(long = "link-listen", value_name = "ADDRESS", conflicts_with = "link_connect")]
+    link_listen: Option<String>,
+
+    /// Plugs the link cable into another instance listening on `<host>:<port>` or `unix:<path>`.
+    #[clap(long = "link-connect", value_name = "ADDRESS")]
+    link_connect: Option<String>,
+
+    /// Time source of the cartridge real-time clock, `emulated` making runs deterministic.
+    #[clap(long = "rtc-clock", value_name = "CLOCK", value_enum, default_value_t = RtcClock::System)]
+    rtc_clock: RtcClock,
+}
+
+/// Tools run instead of the emulator.
+#[derive(Subcommand)]
+enum Command {
+    /// Runs Mooneye test ROMs and reports whether each of them passed.
+    Mooneye {
+        /// Path of a ROM, or of a directory searched recursively for ROMs.
+        path: PathBuf,
+
+        /// Emulated seconds after which a ROM that did not report its result fails.
+        #[clap(long = "timeout", value_name = "SECONDS", default_value_t = 30)]
+        timeout: u32,
+    },
+    /// Disassembles ROM banks, printing the address, raw bytes and instruction of every line.
+    Disasm {
+        /// Path of the ROM file.
+        rom: PathBuf,
+
+        /// Bank, or range of banks as `<first>-<last>`, to disassemble, in hexadecimal.
+        #[clap(long = "banks", value_name = "BANKS", default_value = "0", value_parser = parse_bank_range)]
+        banks: (usize, usize),
+
+        /// First address to disassemble in each bank, in hexadecimal.
+        #[clap(long = "start", value_name = "ADDR", default_value = "0000", value_parser = debugger::parse_number)]
+        start: u16,
+
+        /// Last address to disassemble in each bank, in hexadecimal.
+        #[clap(long = "end", value_name = "ADDR", default_value = "7FFF", value_parser = debugger::parse_number)]
+        end: u16,
+
+        /// Path of the symbol file of the ROM, `<rom>.sym` by default.
+        #[clap(long = "sym", value_name = "PATH")]
+        sym: Option<PathBuf>,
+    },
+    /// Runs a ROM while comparing its trace with a reference in the Gameboy Doctor format,
+    /// stopping at the first difference.
+    TraceDiff {
+        /// Path of the ROM file.
+        rom: PathBuf,
+
+        /// Path of the reference trace.
+        reference: PathBuf,
+
+        /// Number of previous instructions printed when the traces differ.
+        #[clap(long = "context", value_name = "N", default_value_t = 10)]
+        context: usize,
+
+        /// Reads LY (FF44) as 0x90, as Gameboy Doctor expects.
+        #[clap(long = "stub-ly")]
+        stub_ly: bool,
+
+        /// Emulated seconds after which the comparison stops.
+        #[clap(long = "timeout", value_name = "SECONDS", default_value_t = 120)]
+        timeout: u32,
+
+        /// Path of the symbol file of the ROM, `<rom>.sym` by default.
+        #[clap(long = "sym", value_name = "PATH")]
+        sym: Option<PathBuf>,
+    },
+}
+
+/// Parses a hexadecimal bank number or a `<first>-<last>` range of banks.
+fn parse_bank_range(banks: &str) -> Result<(usize, usize), String> {
+    let (first, last) = match banks.split_once('-') {
+        Some((first, last)) => (debugger::parse_bank(first)?, debugger::parse_bank(last)?),
+        None => (debugger::parse_bank(banks)?, debugger::parse_bank(banks)?),
+    };
+    if last < first {
+        return Err(format!("Invalid bank range {}, the last bank is before the first", banks));
+    }
+    Ok((first, last))
+}
+
+/// Where and how often frames are dumped in headless mode.
+struct ScreenshotOptions {
+    path: PathBuf,
+    format: ImageFormat,
+    every: Option<u32>,
+}
+
+impl ScreenshotOptions {
+    fn from_opt(opt: &Opt) -> Result<Option<Self>, String> {
+        let Some(path) = &opt.screenshot else {
+            return Ok(None);
+        };
+        let format = ImageFormat::from_path(path)
+            .ok_or_else(|| format!("Unsupported screenshot format for {}, expected .png or .ppm", path.display()))?;
+        if opt.screenshot_every == Some(0) {
+            return Err(String::from("--screenshot-every must be greater than 0"));
+        }
+        Ok(Some(ScreenshotOptions { path: path.clone(), format, every: opt.screenshot_every }))
+    }
 }
 
 /// Initializes the logger with debug level filtering if the `debug_assertions` feature is enabled.
@@ -52,17 +265,118 @@ fn init_logger() {
         .init();
 }
 
+/// Sleeps until a frame started at `frame_start` would be over on hardware.
+fn wait_for_frame(frame_start: Instant, cycles: u32) {
+    let elapsed = frame_start.elapsed();
+    let frame_duration = cycles_to_time(cycles);
+    if frame_duration < elapsed {
+        warn!("Overshoot: {} ns > {} ns", elapsed.as_nanos(), frame_duration.as_nanos());
+    } else {
+        thread::sleep(frame_duration - elapsed);
+    }
+}
+
+/// Runs the emulation until the PPU completes a frame, or for a frame period while the LCD is off.
+///
+/// Returns the number of T-cycles spent.
+fn run_frame(cpu: &mut CPU, mmu: &mut MMU) -> u32 {
+    let start = mmu.cycles();
+    while mmu.cycles() - start < CYCLES_PER_FRAME as u64 {
+        let pc = cpu.registers().pc;
+        // TODO(henrick) Step error handling
+        cpu.step(mmu);
+        for hit in mmu.take_watchpoint_hits() {
+            info!("Watchpoint hit by PC={:04X}: {}", pc, hit);
+        }
+        if mmu.ppu.take_frame() {
+            break;
+        }
+    }
+    (mmu.cycles() - start) as u32
+}
+
+/// Applies the replayed input events due before running `frame`.
+fn replay_input(mmu: &mut MMU, replay: &mut Option<InputReplay>, frame: u32) {
+    let Some(replay) = replay else {
+        return;
+    };
+    for event in replay.events_for_frame(frame) {
+        debug!("Frame {}: {:?} {:?}", frame, event.action, event.button);
+        match event.action {
+            InputAction::Press => mmu.press_button(event.button),
+            InputAction::Release => mmu.release_button(event.button),
+        }
+    }
+}
+
+/// Writes the samples produced by the APU since the last call to the WAV file, if any.
+fn write_audio(mmu: &mut MMU, wav: &mut Option<WavWriter>) {
+    let Some(writer) = wav else {
+        return;
+    };
+    if let Err(err) = writer.write_samples(&mmu.apu.take_samples()) {
+        error!("Failed to write audio, stopping WAV output: {}", err);
+        *wav = None;
+    }
+}
+
+/// Writes the current frame to `path`, reporting failures without stopping the emulation.
+fn save_screenshot(mmu: &MMU, path: &Path, format: ImageFormat) {
+    match screenshot::save(path, format, mmu.ppu.framebuffer()) {
+        Ok(()) => info!("Frame dumped to {}", path.display()),
+        Err(err) => error!("Failed to dump frame to {}: {}", path.display(), err),
+    }
+}
+
+/// Runs `frames` frames without pacing, dumping them as requested.
+fn run_headless(cpu: &mut CPU, mmu: &mut MMU, replay: &mut Option<InputReplay>, wav: &mut Option<WavWriter>,
+                frames: u32, screenshot: Option<ScreenshotOptions>) {
+    for frame in 1..=frames {
+        replay_input(mmu, replay, frame);
+        run_frame(cpu, mmu);
+        write_audio(mmu, wav);
+        if let Some(ScreenshotOptions { path, format, every: Some(every) }) = &screenshot {
+            if frame % every == 0 {
+                save_screenshot(mmu, &screenshot::numbered_path(path, frame), *format);
+            }
+        }
+        if signals::take_interrupt() {
+            info!("Interrupted after {} frames", frame);
+            break;
+        }
+    }
+    if let Some(ScreenshotOptions { path, format, every: None }) = &screenshot {
+        save_screenshot(mmu, path, *format);
+    }
+}
+
+/// Opens `path` for writing, `-` being stdout, exiting on failure.
+fn create_output(path: &Path) -> Box<dyn Write> {
+    if path.as_os_str() == "-" {
+        return Box::new(io::stdout());
+    }
+    match File::create(path) {
+        Ok(file) => Box::new(file),
+        Err(err) => {
+            error!("Failed to create {}: {}", path.display(), err);
+            process::exit(1);
+        }
+    }
+}
+
 /// Runs the ROM on the emulator.
 ///
 /// # Arguments
 ///
 /// * `rom` - The ROM to be loaded and executed.
+/// * `rom_path` - The path the ROM was loaded from, next to which its save is kept.
+/// * `opt` - The command-line options selecting how the ROM is run.
 ///
 /// # Examples
 ///
 /// ```rust
 /// let rom = Rom::new("game.rom");
-/// run_rom(rom);
+/// run_rom(rom, "game.rom", &opt);
 /// ```
 ///
 /// # Panics
@@ -72,14 +386,139 @@ fn init_logger() {
 /// # Safety
 ///
 /// This function assumes that the ROM has been loaded and validated successfully.
-fn run_rom(rom: Rom) {
+fn run_rom(rom: Rom, rom_path: &str, opt: &Opt) {
     debug!("ROM loaded and validated successfully");
     rom.print_info();
-    let mut mmu = MMU::new().with_rom(rom);
-    let mut cpu = CPU::new();
-    loop {
-        // TODO(henrick) Step error handling
-        cpu.step(&mut mmu);
+    let screenshot = match ScreenshotOptions::from_opt(opt) {
+        Ok(screenshot) => screenshot,
+        Err(err) => {
+            error!("{}", err);
+            process::exit(1);
+        }
+    };
+    let mut mmu = match MMU::new().with_rom(rom, opt.rtc_clock) {
+        Ok(mmu) => mmu,
+        Err(err) => {
+            error!("{}", err);
+            process::exit(1);
+        }
+    };
+    if opt.stub_ly {
+        mmu = mmu.with_stubbed_ly();
+    }
+    if let Some(path) = &opt.serial_out {
+        mmu = mmu.with_serial_endpoint(Box::new(OutputEndpoint::new(create_output(path))));
+    }
+    let link = match (&opt.link_listen, &opt.link_connect) {
+        (Some(address), _) => Some(Link::listen(&LinkAddress::parse(address))),
+        (_, Some(address)) => Some(Link::connect(&LinkAddress::parse(address))),
+        (None, None) => None,
+    };
+    match link {
+        Some(Ok(link)) => mmu = mmu.with_serial_endpoint(Box::new(link)),
+        Some(Err(err)) => {
+            error!("Failed to plug the link cable in: {}", err);
+            process::exit(1);
+        }
+        None => (),
+    }
+    for spec in &opt.watch {
+        match Watchpoint::parse(spec, !opt.debug) {
+            Ok(watchpoint) => mmu.add_watchpoint(watchpoint),
+            Err(err) => {
+                error!("{}", err);
+                process::exit(1);
+            }
+        }
+    }
+    let mut save = SaveFile::for_rom(Path::new(rom_path));
+    if let Err(err) = save.load(mmu.cartridge.as_mut()) {
+        error!("{}", err);
+        process::exit(1);
+    }
+    let symbols = match Symbols::find(Path::new(rom_path), opt.sym.as_deref()) {
+        Ok(symbols) => symbols,
+        Err(err) => {
+            error!("{}", err);
+            process::exit(1);
+        }
+    };
+    let mut cpu = CPU::new().with_symbols(symbols.clone());
+    let state_slots = StateSlots::for_rom(Path::new(rom_path));
+    if let Some(slot) = opt.load_state {
+        match state_slots.load(slot, &mut cpu, &mut mmu) {
+            Ok(path) => info!("State loaded from {}", path.display()),
+            Err(err) => {
+                error!("{}", err);
+                process::exit(1);
+            }
+        }
+    }
+    if let Some(path) = &opt.trace {
+        cpu = cpu.with_tracer(Box::new(Trace::new(Box::new(BufWriter::new(create_output(path))))));
+    }
+    let mut wav = match &opt.wav_out {
+        Some(path) => match WavWriter::create(path, SAMPLE_RATE, OUTPUT_CHANNELS) {
+            Ok(writer) => {
+                mmu.apu.enable_output();
+                Some(writer)
+            }
+            Err(err) => {
+                error!("Failed to create {}: {}", path.display(), err);
+                process::exit(1);
+            }
+        },
+        None => None,
+    };
+    let mut replay = match &opt.input_replay {
+        Some(path) => match InputReplay::from_path(path) {
+            Ok(replay) => Some(replay),
+            Err(err) => {
+                error!("Failed to load input replay {}: {}", path.display(), err);
+                process::exit(1);
+            }
+        },
+        None => None,
+    };
+    // Ctrl-C stops the emulation instead of killing the process, so that the save is flushed and
+    // the debugger gets its prompt back.
+    if let Err(err) = signals::install() {
+        warn!("{}", err);
+    }
+    if opt.debug {
+        // The debugger prints the state of the CPU on demand, instead of logging every step.
+        log::set_max_level(log::LevelFilter::Info);
+        Debugger::new().with_symbols(symbols).with_state_slots(state_slots.clone()).run(&mut cpu, &mut mmu);
+    } else if opt.headless {
+        run_headless(&mut cpu, &mut mmu, &mut replay, &mut wav, opt.frames.unwrap_or_default(), screenshot);
+    } else {
+        run_realtime(&mut cpu, &mut mmu, &mut replay, &mut wav, &mut save);
+    }
+    if let Some(slot) = opt.save_state {
+        match state_slots.save(slot, &cpu, &mmu) {
+            Ok(path) => info!("State saved to {}", path.display()),
+            Err(err) => error!("{}", err),
+        }
+    }
+    save.flush(mmu.cartridge.as_mut());
+}
+
+/// Runs the emulation at the speed of the hardware until Ctrl-C is pressed, flushing the save
+/// every `SAVE_INTERVAL_FRAMES` frames.
+fn run_realtime(cpu: &mut CPU, mmu: &mut MMU, replay: &mut Option<InputReplay>, wav: &mut Option<WavWriter>, save: &mut SaveFile) {
+    for frame in 1.. {
+        replay_input(mmu, replay, frame);
+        let frame_start = Instant::now();
+        let cycles = run_frame(cpu, mmu);
+        write_audio(mmu, wav);
+        if signals::take_interrupt() {
+            info!("Interrupted, exiting");
+            return;
+        }
+        if frame % SAVE_INTERVAL_FRAMES == 0 {
+            save.flush(mmu.cartridge.as_mut());
+        }
+        wait_for_frame(frame_start, cycles);
     }
 }
 
@@ -87,10 +526,49 @@ fn run_rom(rom: Rom) {
 fn main() {
     init_logger();
     let opt = Opt::parse();
-    match Rom::from_path(&opt.rom) {
+    match &opt.command {
+        Some(Command::Mooneye { path, timeout }) => match mooneye::run(path, *timeout) {
+            Ok(true) => process::exit(0),
+            Ok(false) => process::exit(1),
+            Err(err) => {
+                error!("{}", err);
+                process::exit(1);
+            }
+        },
+        Some(Command::Disasm { rom: path, banks: (first_bank, last_bank), start, end, sym }) => {
+            let result = fs::read(path)
+                .map_err(|err| format!("Failed to read {}: {}", path.display(), err))
+                .and_then(|rom| Ok((rom, Symbols::find(path, sym.as_deref())?)))
+                .and_then(|(rom, symbols)| cpu::dump_disassembly(&rom, symbols, *first_bank, *last_bank, *start, *end));
+            if let Err(err) = result {
+                error!("{}", err);
+                process::exit(1);
+            }
+            return;
+        }
+        Some(Command::TraceDiff { rom, reference, context, stub_ly, timeout, sym }) => {
+            // The divergence report replaces the logging of every step.
+            log::set_max_level(log::LevelFilter::Info);
+            let result = Symbols::find(rom, sym.as_deref()).and_then(|symbols| {
+                let options = trace_diff::Options { symbols, context: *context, stub_ly: *stub_ly, timeout: *timeout };
+                trace_diff::run(rom, reference, options)
+            });
+            match result {
+                Ok(true) => process::exit(0),
+                Ok(false) => process::exit(1),
+                Err(err) => {
+                    error!("{}", err);
+                    process::exit(1);
+                }
+            }
+        }
+        None => (),
+    }
+    let rom_path = opt.rom.as_deref().expect("--rom is required without a subcommand");
+    match Rom::from_path(rom_path) {
         Ok(rom) => {
             if rom.validate() {
-                run_rom(rom);
+                run_rom(rom, rom_path, &opt);
             } else {
                 error!("Invalid ROM!");
                 process::exit(1);
@@ -0,0 +1,172 @@
+use crate::mbc::{bank_count, load_ram, read_banked, write_banked, Mapper, RAM_BANK_SIZE, ROM_BANK_SIZE};
+use crate::save_state::{Snapshot, StateReader, StateWriter};
+use crate::types::Memory;
+
+/// MBC1: up to 2 MiB of ROM and 32 KiB of RAM.
+///
+/// The 5-bit BANK1 register selects the ROM bank mapped at 0x4000-0x7FFF, 0 being turned into 1:
+/// banks 0x20, 0x40 and 0x60 can therefore not be mapped there, 0x21, 0x41 and 0x61 being
+/// selected instead. The 2-bit BANK2 register provides the upper ROM bank bits, and in mode 1
+/// also applies to the 0x0000-0x3FFF area and selects the RAM bank.
+pub(crate) struct Mbc1 {
+    rom: Memory,
+    ram: Memory,
+    battery: bool,
+    rom_banks: usize,
+    ram_banks: usize,
+    ram_enabled: bool,
+    bank1: u8,
+    bank2: u8,
+    mode: bool,
+}
+
+impl Mbc1 {
+    pub(crate) fn new(rom: Memory, ram_size: usize, battery: bool) -> Self {
+        let ram = Memory { data: vec![0; ram_size] };
+        Mbc1 {
+            rom_banks: bank_count(&rom, ROM_BANK_SIZE),
+            ram_banks: bank_count(&ram, RAM_BANK_SIZE),
+            rom,
+            ram,
+            battery,
+            ram_enabled: false,
+            bank1: 1,
+            bank2: 0,
+            mode: false,
+        }
+    }
+
+    fn ram_bank(&self) -> usize {
+        let bank = if self.mode { self.bank2 as usize } else { 0 };
+        bank & (self.ram_banks - 1)
+    }
+}
+
+impl Mapper for Mbc1 {
+    fn read_rom(&self, addr: u16) -> u8 {
+        let bank = match addr {
+            0x0000..=0x3FFF if self.mode => (self.bank2 as usize) << 5,
+            0x0000..=0x3FFF => 0,
+            _ => ((self.bank2 as usize) << 5) | self.bank1 as usize,
+        };
+        read_banked(&self.rom, bank & (self.rom_banks - 1), ROM_BANK_SIZE, addr & 0x3FFF)
+    }
+
+    fn current_rom_bank(&self) -> usize {
+        (((self.bank2 as usize) << 5) | self.bank1 as usize) & (self.rom_banks - 1)
+    }
+
+    fn write_rom(&mut self, addr: u16, value: u8) {
+        match addr {
+            0x0000..=0x1FFF => self.ram_enabled = value & 0x0F == 0x0A,
+            0x2000..=0x3FFF => self.bank1 = match value & 0x1F {
+                0 => 1,
+                bank => bank,
+            },
+            0x4000..=0x5FFF => self.bank2 = value & 0b11,
+            _ => self.mode = value & 1 != 0,
+        }
+    }
+
+    fn read_ram(&self, addr: u16) -> u8 {
+        if !self.ram_enabled {
+            return 0xFF;
+        }
+        read_banked(&self.ram, self.ram_bank(), RAM_BANK_SIZE, addr - 0xA000)
+    }
+
+    fn write_ram(&mut self, addr: u16, value: u8) {
+        if self.ram_enabled {
+            let bank = self.ram_bank();
+            write_banked(&mut self.ram, bank, RAM_BANK_SIZE, addr - 0xA000, value);
+        }
+    }
+
+    fn has_battery(&self) -> bool {
+        self.battery
+    }
+
+    fn save_data(&mut self) -> Vec<u8> {
+        self.ram.data.clone()
+    }
+
+    fn load_data(&mut self, data: &[u8]) -> Result<(), String> {
+        load_ram(&mut self.ram, data)
+    }
+}
+
+impl Snapshot for Mbc1 {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_memory(&self.ram);
+        state.write_bool(self.ram_enabled);
+        state.write_u8(self.bank1);
+        state.write_u8(self.bank2);
+        state.write_bool(self.mode);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        state.read_memory(&mut self.ram)?;
+        self.ram_enabled = state.read_bool()?;
+        self.bank1 = state.read_u8()?;
+        self.bank2 = state.read_u8()?;
+        self.mode = state.read_bool()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mbc::numbered_rom;
+
+    #[test]
+    fn banks_0x20_0x40_and_0x60_are_remapped_to_the_next_bank() {
+        let mut mbc = Mbc1::new(numbered_rom(128), 0, false);
+        mbc.write_rom(0x2000, 0x00);
+        assert_eq!(mbc.read_rom(0x4000), 0x01);
+        for (bank2, bank) in [(1, 0x21), (2, 0x41), (3, 0x61)] {
+            mbc.write_rom(0x4000, bank2);
+            assert_eq!(mbc.read_rom(0x4000), bank);
+            assert_eq!(mbc.current_rom_bank(), bank as usize);
+        }
+        mbc.write_rom(0x2000, 0x05);
+        assert_eq!(mbc.read_rom(0x4000), 0x65);
+    }
+
+    #[test]
+    fn mode_1_applies_bank2_to_the_first_rom_area() {
+        let mut mbc = Mbc1::new(numbered_rom(128), 0, false);
+        mbc.write_rom(0x4000, 2);
+        assert_eq!(mbc.read_rom(0x0000), 0x00);
+        mbc.write_rom(0x6000, 1);
+        assert_eq!(mbc.read_rom(0x0000), 0x40);
+    }
+
+    #[test]
+    fn mode_1_selects_the_ram_bank() {
+        let mut mbc = Mbc1::new(numbered_rom(4), 4 * RAM_BANK_SIZE, false);
+        mbc.write_rom(0x0000, 0x0A);
+        mbc.write_rom(0x6000, 1);
+        for bank in 0..4 {
+            mbc.write_rom(0x4000, bank);
+            mbc.write_ram(0xA000, 0x10 + bank);
+        }
+        for bank in 0..4 {
+            mbc.write_rom(0x4000, bank);
+            assert_eq!(mbc.read_ram(0xA000), 0x10 + bank);
+        }
+
+        // In mode 0, bank 0 is always mapped.
+        mbc.write_rom(0x6000, 0);
+        assert_eq!(mbc.read_ram(0xA000), 0x10);
+    }
+
+    #[test]
+    fn ram_reads_as_ff_until_enabled() {
+        let mut mbc = Mbc1::new(numbered_rom(4), RAM_BANK_SIZE, false);
+        mbc.write_ram(0xA000, 0x12);
+        assert_eq!(mbc.read_ram(0xA000), 0xFF);
+        mbc.write_rom(0x0000, 0x0A);
+        assert_eq!(mbc.read_ram(0xA000), 0x00);
+    }
+}
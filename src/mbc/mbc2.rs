@@ -0,0 +1,131 @@
+use crate::mbc::{bank_count, load_ram, read_banked, Mapper, ROM_BANK_SIZE};
+use crate::save_state::{Snapshot, StateReader, StateWriter};
+use crate::types::Memory;
+
+/// Size of the built-in RAM of MBC2, made of 512 4-bit values.
+const RAM_SIZE: usize = 512;
+
+/// MBC2: up to 256 KiB of ROM and 512x4 bits of built-in RAM.
+///
+/// Bit 8 of the address of a write to 0x0000-0x3FFF selects the register: RAM enable when clear,
+/// ROM bank number when set. The RAM is mirrored over the whole 0xA000-0xBFFF area.
+pub(crate) struct Mbc2 {
+    rom: Memory,
+    ram: Memory,
+    battery: bool,
+    rom_banks: usize,
+    ram_enabled: bool,
+    rom_bank: u8,
+}
+
+impl Mbc2 {
+    pub(crate) fn new(rom: Memory, battery: bool) -> Self {
+        Mbc2 {
+            rom_banks: bank_count(&rom, ROM_BANK_SIZE),
+            rom,
+            ram: Memory { data: vec![0; RAM_SIZE] },
+            battery,
+            ram_enabled: false,
+            rom_bank: 1,
+        }
+    }
+}
+
+impl Mapper for Mbc2 {
+    fn read_rom(&self, addr: u16) -> u8 {
+        let bank = match addr {
+            0x0000..=0x3FFF => 0,
+            _ => self.current_rom_bank(),
+        };
+        read_banked(&self.rom, bank, ROM_BANK_SIZE, addr & 0x3FFF)
+    }
+
+    fn current_rom_bank(&self) -> usize {
+        self.rom_bank as usize & (self.rom_banks - 1)
+    }
+
+    fn write_rom(&mut self, addr: u16, value: u8) {
+        match addr {
+            0x0000..=0x3FFF if addr & 0x0100 == 0 => self.ram_enabled = value & 0x0F == 0x0A,
+            0x0000..=0x3FFF => self.rom_bank = match value & 0x0F {
+                0 => 1,
+                bank => bank,
+            },
+            _ => (),
+        }
+    }
+
+    fn read_ram(&self, addr: u16) -> u8 {
+        if !self.ram_enabled {
+            return 0xFF;
+        }
+        // Only the lower nibble is stored, the upper one reading as 1s.
+        0xF0 | self.ram.data[(addr as usize - 0xA000) % RAM_SIZE]
+    }
+
+    fn write_ram(&mut self, addr: u16, value: u8) {
+        if self.ram_enabled {
+            self.ram.data[(addr as usize - 0xA000) % RAM_SIZE] = value & 0x0F;
+        }
+    }
+
+    fn has_battery(&self) -> bool {
+        self.battery
+    }
+
+    fn save_data(&mut self) -> Vec<u8> {
+        self.ram.data.clone()
+    }
+
+    fn load_data(&mut self, data: &[u8]) -> Result<(), String> {
+        load_ram(&mut self.ram, data)?;
+        self.ram.data.iter_mut().for_each(|value| *value &= 0x0F);
+        Ok(())
+    }
+}
+
+impl Snapshot for Mbc2 {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_memory(&self.ram);
+        state.write_bool(self.ram_enabled);
+        state.write_u8(self.rom_bank);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        state.read_memory(&mut self.ram)?;
+        self.ram_enabled = state.read_bool()?;
+        self.rom_bank = state.read_u8()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mbc::numbered_rom;
+
+    #[test]
+    fn address_bit_8_selects_the_register() {
+        let mut mbc = Mbc2::new(numbered_rom(16), false);
+        mbc.write_rom(0x0100, 0x0A);
+        assert_eq!(mbc.read_rom(0x4000), 0x0A);
+        assert_eq!(mbc.read_ram(0xA000), 0xFF);
+
+        mbc.write_rom(0x2000, 0x0A);
+        assert_eq!(mbc.read_ram(0xA000), 0xF0);
+        mbc.write_rom(0x3F00, 0x00);
+        assert_eq!(mbc.read_rom(0x4000), 0x01);
+        assert_eq!(mbc.current_rom_bank(), 1);
+    }
+
+    #[test]
+    fn ram_stores_the_lower_nibble_and_is_mirrored() {
+        let mut mbc = Mbc2::new(numbered_rom(2), false);
+        mbc.write_rom(0x0000, 0x0A);
+        mbc.write_ram(0xA000, 0xAB);
+        assert_eq!(mbc.read_ram(0xA000), 0xFB);
+        assert_eq!(mbc.read_ram(0xA200), 0xFB);
+        assert_eq!(mbc.read_ram(0xBE00), 0xFB);
+        assert_eq!(mbc.save_data()[0], 0x0B);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::mbc::rtc::Rtc;
+use crate::mbc::{bank_count, read_banked, write_banked, Mapper, RAM_BANK_SIZE, ROM_BANK_SIZE};
+use crate::save_state::{Snapshot, StateReader, StateWriter};
+use crate::types::Memory;
+
+/// MBC3: up to 2 MiB of ROM, 32 KiB of RAM and an optional real-time clock.
+///
+/// The 7-bit ROM bank number selects the bank mapped at 0x4000-0x7FFF, 0 being turned into 1.
+/// The RAM bank register maps either a RAM bank (0x00-0x03) or an RTC register (0x08-0x0C) at
+/// 0xA000-0xBFFF.
+pub(crate) struct Mbc3 {
+    rom: Memory,
+    ram: Memory,
+    rtc: Option<Rtc>,
+    battery: bool,
+    rom_banks: usize,
+    ram_banks: usize,
+    ram_enabled: bool,
+    rom_bank: u8,
+    ram_bank: u8,
+}
+
+impl Mbc3 {
+    pub(crate) fn new(rom: Memory, ram_size: usize, rtc: Option<Rtc>, battery: bool) -> Self {
+        let ram = Memory { data: vec![0; ram_size] };
+        Mbc3 {
+            rom_banks: bank_count(&rom, ROM_BANK_SIZE),
+            ram_banks: bank_count(&ram, RAM_BANK_SIZE),
+            rom,
+            ram,
+            rtc,
+            battery,
+            ram_enabled: false,
+            rom_bank: 1,
+            ram_bank: 0,
+        }
+    }
+}
+
+impl Mapper for Mbc3 {
+    fn read_rom(&self, addr: u16) -> u8 {
+        let bank = match addr {
+            0x0000..=0x3FFF => 0,
+            _ => self.current_rom_bank(),
+        };
+        read_banked(&self.rom, bank, ROM_BANK_SIZE, addr & 0x3FFF)
+    }
+
+    fn current_rom_bank(&self) -> usize {
+        self.rom_bank as usize & (self.rom_banks - 1)
+    }
+
+    fn write_rom(&mut self, addr: u16, value: u8) {
+        match addr {
+            0x0000..=0x1FFF => self.ram_enabled = value & 0x0F == 0x0A,
+            0x2000..=0x3FFF => self.rom_bank = match value & 0x7F {
+                0 => 1,
+                bank => bank,
+            },
+            0x4000..=0x5FFF => self.ram_bank = value & 0x0F,
+            _ => if let Some(rtc) = &mut self.rtc {
+                rtc.write_latch(value);
+            },
+        }
+    }
+
+    fn read_ram(&self, addr: u16) -> u8 {
+        if !self.ram_enabled {
+            return 0xFF;
+        }
+        match (self.ram_bank, &self.rtc) {
+            (0x00..=0x03, _) => {
+                let bank = self.ram_bank as usize & (self.ram_banks - 1);
+                read_banked(&self.ram, bank, RAM_BANK_SIZE, addr - 0xA000)
+            }
+            (0x08..=0x0C, Some(rtc)) => rtc.read(self.ram_bank),
+            _ => 0xFF,
+        }
+    }
+
+    fn write_ram(&mut self, addr: u16, value: u8) {
+        if !self.ram_enabled {
+            return;
+        }
+        match (self.ram_bank, &mut self.rtc) {
+            (0x00..=0x03, _) => {
+                let bank = self.ram_bank as usize & (self.ram_banks - 1);
+                write_banked(&mut self.ram, bank, RAM_BANK_SIZE, addr - 0xA000, value);
+            }
+            (0x08..=0x0C, Some(rtc)) => rtc.write(self.ram_bank, value),
+            _ => (),
+        }
+    }
+
+    fn tick(&mut self, cycles: u8) {
+        if let Some(rtc) = &mut self.rtc {
+            rtc.tick(cycles);
+        }
+    }
+
+    fn has_battery(&self) -> bool {
+        self.battery
+    }
+
+    fn save_data(&mut self) -> Vec<u8> {
+        let mut data = self.ram.data.clone();
+        if let Some(rtc) = &mut self.rtc {
+            rtc.save(&mut data);
+        }
+        data
+    }
+
+    fn load_data(&mut self, data: &[u8]) -> Result<(), String> {
+        let ram_size = self.ram.data.len();
+        if data.len() < ram_size {
+            return Err(format!("Save data too small: {} bytes, expected {}", data.len(), ram_size));
+        }
+        let (ram, trailer) = data.split_at(ram_size);
+        self.ram.data.copy_from_slice(ram);
+        match &mut self.rtc {
+            // Saves written without the RTC trailer keep the clock at its default state.
+            Some(rtc) if !trailer.is_empty() => rtc.load(trailer),
+            None if !trailer.is_empty() => Err(format!("Save data too large: {} bytes, expected {}", data.len(), ram_size)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Whether the cartridge has an RTC is not saved, as it is fixed by the cartridge type.
+impl Snapshot for Mbc3 {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_memory(&self.ram);
+        if let Some(rtc) = &self.rtc {
+            rtc.save_state(state);
+        }
+        state.write_bool(self.ram_enabled);
+        state.write_u8(self.rom_bank);
+        state.write_u8(self.ram_bank);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        state.read_memory(&mut self.ram)?;
+        if let Some(rtc) = &mut self.rtc {
+            rtc.load_state(state)?;
+        }
+        self.ram_enabled = state.read_bool()?;
+        self.rom_bank = state.read_u8()?;
+        self.ram_bank = state.read_u8()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mbc::numbered_rom;
+
+    #[test]
+    fn rom_bank_register_uses_7_bits_and_maps_bank_0_to_bank_1() {
+        let mut mbc = Mbc3::new(numbered_rom(128), 0, None, false);
+        mbc.write_rom(0x2000, 0x00);
+        assert_eq!(mbc.read_rom(0x4000), 0x01);
+        mbc.write_rom(0x2000, 0x7F);
+        assert_eq!(mbc.read_rom(0x4000), 0x7F);
+        mbc.write_rom(0x2000, 0x80);
+        assert_eq!(mbc.read_rom(0x4000), 0x01);
+        mbc.write_rom(0x2000, 0x85);
+        assert_eq!(mbc.read_rom(0x4000), 0x05);
+        assert_eq!(mbc.read_rom(0x0000), 0x00);
+    }
+
+    #[test]
+    fn ram_bank_register_selects_ram_banks_0_to_3() {
+        let mut mbc = Mbc3::new(numbered_rom(4), 4 * RAM_BANK_SIZE, None, false);
+        mbc.write_rom(0x0000, 0x0A);
+        for bank in 0..4 {
+            mbc.write_rom(0x4000, bank);
+            mbc.write_ram(0xA000, 0x10 + bank);
+            mbc.write_ram(0xBFFF, 0x20 + bank);
+        }
+        for bank in 0..4 {
+            mbc.write_rom(0x4000, bank);
+            assert_eq!(mbc.read_ram(0xA000), 0x10 + bank);
+            assert_eq!(mbc.read_ram(0xBFFF), 0x20 + bank);
+        }
+
+        // Without an RTC, the RTC registers read as open bus.
+        mbc.write_rom(0x4000, 0x08);
+        assert_eq!(mbc.read_ram(0xA000), 0xFF);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::mbc::{bank_count, load_ram, read_banked, write_banked, Mapper, RAM_BANK_SIZE, ROM_BANK_SIZE};
+use crate::save_state::{Snapshot, StateReader, StateWriter};
+use crate::types::Memory;
+
+/// MBC5: up to 8 MiB of ROM and 128 KiB of RAM.
+///
+/// The ROM bank number is 9 bits wide, its lower 8 bits being written to 0x2000-0x2FFF and its
+/// 9th bit to 0x3000-0x3FFF. Unlike the other controllers, bank 0 can be mapped at
+/// 0x4000-0x7FFF. On rumble cartridges, bit 3 of the RAM bank register drives the motor.
+pub(crate) struct Mbc5 {
+    rom: Memory,
+    ram: Memory,
+    battery: bool,
+    rom_banks: usize,
+    ram_banks: usize,
+    rumble: bool,
+    ram_enabled: bool,
+    rom_bank: u16,
+    ram_bank: u8,
+}
+
+impl Mbc5 {
+    pub(crate) fn new(rom: Memory, ram_size: usize, rumble: bool, battery: bool) -> Self {
+        let ram = Memory { data: vec![0; ram_size] };
+        Mbc5 {
+            rom_banks: bank_count(&rom, ROM_BANK_SIZE),
+            ram_banks: bank_count(&ram, RAM_BANK_SIZE),
+            rom,
+            ram,
+            rumble,
+            battery,
+            ram_enabled: false,
+            rom_bank: 1,
+            ram_bank: 0,
+        }
+    }
+}
+
+impl Mapper for Mbc5 {
+    fn read_rom(&self, addr: u16) -> u8 {
+        let bank = match addr {
+            0x0000..=0x3FFF => 0,
+            _ => self.current_rom_bank(),
+        };
+        read_banked(&self.rom, bank, ROM_BANK_SIZE, addr & 0x3FFF)
+    }
+
+    fn current_rom_bank(&self) -> usize {
+        self.rom_bank as usize & (self.rom_banks - 1)
+    }
+
+    fn write_rom(&mut self, addr: u16, value: u8) {
+        match addr {
+            0x0000..=0x1FFF => self.ram_enabled = value & 0x0F == 0x0A,
+            0x2000..=0x2FFF => self.rom_bank = (self.rom_bank & 0x100) | value as u16,
+            0x3000..=0x3FFF => self.rom_bank = (self.rom_bank & 0xFF) | (((value & 1) as u16) << 8),
+            0x4000..=0x5FFF => self.ram_bank = if self.rumble { value & 0b0111 } else { value & 0b1111 },
+            _ => (),
+        }
+    }
+
+    fn read_ram(&self, addr: u16) -> u8 {
+        if !self.ram_enabled {
+            return 0xFF;
+        }
+        let bank = self.ram_bank as usize & (self.ram_banks - 1);
+        read_banked(&self.ram, bank, RAM_BANK_SIZE, addr - 0xA000)
+    }
+
+    fn write_ram(&mut self, addr: u16, value: u8) {
+        if self.ram_enabled {
+            let bank = self.ram_bank as usize & (self.ram_banks - 1);
+            write_banked(&mut self.ram, bank, RAM_BANK_SIZE, addr - 0xA000, value);
+        }
+    }
+
+    fn has_battery(&self) -> bool {
+        self.battery
+    }
+
+    fn save_data(&mut self) -> Vec<u8> {
+        self.ram.data.clone()
+    }
+
+    fn load_data(&mut self, data: &[u8]) -> Result<(), String> {
+        load_ram(&mut self.ram, data)
+    }
+}
+
+impl Snapshot for Mbc5 {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_memory(&self.ram);
+        state.write_bool(self.ram_enabled);
+        state.write_u16(self.rom_bank);
+        state.write_u8(self.ram_bank);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        state.read_memory(&mut self.ram)?;
+        self.ram_enabled = state.read_bool()?;
+        self.rom_bank = state.read_u16()?;
+        self.ram_bank = state.read_u8()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mbc::numbered_rom;
+
+    fn bank_at_0x4000(mbc: &Mbc5) -> u16 {
+        u16::from_le_bytes([mbc.read_rom(0x4000), mbc.read_rom(0x4001)])
+    }
+
+    #[test]
+    fn rom_bank_number_is_9_bits_wide() {
+        let mut mbc = Mbc5::new(numbered_rom(512), 0, false, false);
+        mbc.write_rom(0x2000, 0x23);
+        mbc.write_rom(0x3000, 0x01);
+        assert_eq!(bank_at_0x4000(&mbc), 0x123);
+        mbc.write_rom(0x2000, 0xFF);
+        assert_eq!(bank_at_0x4000(&mbc), 0x1FF);
+        mbc.write_rom(0x3000, 0x00);
+        assert_eq!(bank_at_0x4000(&mbc), 0x0FF);
+    }
+
+    #[test]
+    fn bank_0_can_be_mapped_at_0x4000() {
+        let mut mbc = Mbc5::new(numbered_rom(512), 0, false, false);
+        mbc.write_rom(0x2000, 0x00);
+        assert_eq!(bank_at_0x4000(&mbc), 0);
+        assert_eq!(mbc.current_rom_bank(), 0);
+    }
+
+    #[test]
+    fn rumble_bit_does_not_select_ram_banks() {
+        let mut mbc = Mbc5::new(numbered_rom(2), 16 * RAM_BANK_SIZE, true, false);
+        mbc.write_rom(0x0000, 0x0A);
+        mbc.write_rom(0x4000, 0x01);
+        mbc.write_ram(0xA000, 0x42);
+        mbc.write_rom(0x4000, 0x09);
+        assert_eq!(mbc.read_ram(0xA000), 0x42);
+    }
+}
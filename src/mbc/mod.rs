@@ -0,0 +1,183 @@
+use crate::cartridge::CartridgeType;
+use crate::mbc::mbc1::Mbc1;
+use crate::mbc::mbc2::Mbc2;
+use crate::mbc::mbc3::Mbc3;
+use crate::mbc::mbc5::Mbc5;
+use crate::mbc::rtc::Rtc;
+use crate::rom::Rom;
+use crate::save_state::{Snapshot, StateReader, StateWriter};
+use crate::types::Memory;
+
+mod mbc1;
+mod mbc2;
+mod mbc3;
+mod mbc5;
+mod rtc;
+
+pub(crate) use rtc::RtcClock;
+
+pub(crate) const ROM_BANK_SIZE: usize = 0x4000;
+pub(crate) const RAM_BANK_SIZE: usize = 0x2000;
+
+/// Cartridge mapper, decoding the ROM (0x0000-0x7FFF) and external RAM (0xA000-0xBFFF) areas.
+///
+/// Writes to the ROM area never reach the ROM: they set the registers of the memory bank
+/// controller instead. The state of a mapper is its RAM and registers, the ROM being left out.
+pub(crate) trait Mapper: Snapshot {
+    fn read_rom(&self, addr: u16) -> u8;
+
+    fn write_rom(&mut self, addr: u16, value: u8);
+
+    fn read_ram(&self, addr: u16) -> u8;
+
+    fn write_ram(&mut self, addr: u16, value: u8);
+
+    /// ROM bank mapped at 0x4000-0x7FFF.
+    fn current_rom_bank(&self) -> usize {
+        1
+    }
+
+    /// Advances the components of the cartridge clocked alongside the CPU, such as the RTC.
+    fn tick(&mut self, _cycles: u8) {}
+
+    /// Whether the cartridge has a battery keeping its RAM and clock when powered off.
+    fn has_battery(&self) -> bool {
+        false
+    }
+
+    /// Contents of the battery-backed memory, as stored in save files.
+    fn save_data(&mut self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    /// Restores the battery-backed memory from a save file.
+    fn load_data(&mut self, _data: &[u8]) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Builds the mapper matching the cartridge type declared in the ROM header.
+pub(crate) fn from_rom(rom: Rom, rtc_clock: RtcClock) -> Result<Box<dyn Mapper>, String> {
+    let ram_size = rom.header().ram_size.bytes();
+    let mapper: Box<dyn Mapper> = match rom.header().cartridge_type {
+        CartridgeType::RomOnly | CartridgeType::RomRam =>
+            Box::new(RomOnly::new(rom.memory, ram_size, false)),
+        CartridgeType::RomRamBattery =>
+            Box::new(RomOnly::new(rom.memory, ram_size, true)),
+        CartridgeType::Mbc1 | CartridgeType::Mbc1Ram =>
+            Box::new(Mbc1::new(rom.memory, ram_size, false)),
+        CartridgeType::Mbc1RamBattery =>
+            Box::new(Mbc1::new(rom.memory, ram_size, true)),
+        CartridgeType::Mbc2 =>
+            Box::new(Mbc2::new(rom.memory, false)),
+        CartridgeType::Mbc2Battery =>
+            Box::new(Mbc2::new(rom.memory, true)),
+        CartridgeType::Mbc3 | CartridgeType::Mbc3Ram =>
+            Box::new(Mbc3::new(rom.memory, ram_size, None, false)),
+        CartridgeType::Mbc3RamBattery =>
+            Box::new(Mbc3::new(rom.memory, ram_size, None, true)),
+        CartridgeType::Mbc3TimerBattery | CartridgeType::Mbc3TimerRamBattery =>
+            Box::new(Mbc3::new(rom.memory, ram_size, Some(Rtc::new(rtc_clock)), true)),
+        CartridgeType::Mbc5 | CartridgeType::Mbc5Ram =>
+            Box::new(Mbc5::new(rom.memory, ram_size, false, false)),
+        CartridgeType::Mbc5RamBattery =>
+            Box::new(Mbc5::new(rom.memory, ram_size, false, true)),
+        CartridgeType::Mbc5Rumble | CartridgeType::Mbc5RumbleRam =>
+            Box::new(Mbc5::new(rom.memory, ram_size, true, false)),
+        CartridgeType::Mbc5RumbleRamBattery =>
+            Box::new(Mbc5::new(rom.memory, ram_size, true, true)),
+        ref cartridge_type => return Err(format!("Unsupported cartridge type: {:?}", cartridge_type)),
+    };
+    Ok(mapper)
+}
+
+/// Number of `bank_size` banks in `memory`, rounded up to a power of two so that it can be used
+/// to mask bank numbers.
+fn bank_count(memory: &Memory, bank_size: usize) -> usize {
+    memory.data.len().div_ceil(bank_size).max(1).next_power_of_two()
+}
+
+/// Reads `memory` at `offset` within `bank`, open bus reading as 0xFF.
+fn read_banked(memory: &Memory, bank: usize, bank_size: usize, offset: u16) -> u8 {
+    memory.data.get(bank * bank_size + offset as usize).copied().unwrap_or(0xFF)
+}
+
+/// Restores `ram` from save data, which must match its size.
+fn load_ram(ram: &mut Memory, data: &[u8]) -> Result<(), String> {
+    if data.len() != ram.data.len() {
+        return Err(format!("Invalid save data size: {} bytes, expected {}", data.len(), ram.data.len()));
+    }
+    ram.data.copy_from_slice(data);
+    Ok(())
+}
+
+fn write_banked(memory: &mut Memory, bank: usize, bank_size: usize, offset: u16, value: u8) {
+    if let Some(byte) = memory.data.get_mut(bank * bank_size + offset as usize) {
+        *byte = value;
+    }
+}
+
+/// ROM of `banks` banks, each starting with its bank number as a little-endian word.
+#[cfg(test)]
+fn numbered_rom(banks: usize) -> Memory {
+    let mut rom = Memory { data: vec![0; banks * ROM_BANK_SIZE] };
+    for (bank, data) in rom.data.chunks_exact_mut(ROM_BANK_SIZE).enumerate() {
+        data[..2].copy_from_slice(&(bank as u16).to_le_bytes());
+    }
+    rom
+}
+
+/// Cartridge without memory bank controller: 32 KiB of ROM and up to 8 KiB of RAM.
+pub(crate) struct RomOnly {
+    rom: Memory,
+    ram: Memory,
+    battery: bool,
+}
+
+impl RomOnly {
+    pub(crate) fn new(rom: Memory, ram_size: usize, battery: bool) -> Self {
+        RomOnly {
+            rom,
+            ram: Memory { data: vec![0; ram_size.min(RAM_BANK_SIZE)] },
+            battery,
+        }
+    }
+}
+
+impl Mapper for RomOnly {
+    fn read_rom(&self, addr: u16) -> u8 {
+        read_banked(&self.rom, 0, ROM_BANK_SIZE, addr)
+    }
+
+    fn write_rom(&mut self, _addr: u16, _value: u8) {}
+
+    fn read_ram(&self, addr: u16) -> u8 {
+        read_banked(&self.ram, 0, RAM_BANK_SIZE, addr - 0xA000)
+    }
+
+    fn write_ram(&mut self, addr: u16, value: u8) {
+        write_banked(&mut self.ram, 0, RAM_BANK_SIZE, addr - 0xA000, value)
+    }
+
+    fn has_battery(&self) -> bool {
+        self.battery
+    }
+
+    fn save_data(&mut self) -> Vec<u8> {
+        self.ram.data.clone()
+    }
+
+    fn load_data(&mut self, data: &[u8]) -> Result<(), String> {
+        load_ram(&mut self.ram, data)
+    }
+}
+
+impl Snapshot for RomOnly {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_memory(&self.ram);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        state.read_memory(&mut self.ram)
+    }
+}
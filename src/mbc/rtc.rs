@@ -0,0 +1,394 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::ValueEnum;
+
+use crate::clock::CLOCK_SPEED;
+use crate::save_state::{Snapshot, StateReader, StateWriter};
+
+/// Size of the RTC trailer appended to the save file: the current and latched registers as ten
+/// little-endian 32-bit values, followed by the 64-bit UNIX timestamp of the save.
+const RTC_TRAILER_SIZE: usize = 48;
+
+/// Older saves only store a 32-bit timestamp.
+const RTC_TRAILER_SIZE_32: usize = 44;
+
+const DAY_HIGH_MASK: u8 = 0b0000_0001;
+const HALT_MASK: u8 = 0b0100_0000;
+const DAY_CARRY_MASK: u8 = 0b1000_0000;
+
+/// Time source driving the real-time clock.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub(crate) enum RtcClock {
+    /// Follows the wall clock, including while the emulator is not running.
+    System,
+    /// Follows the emulated CPU cycles, for deterministic runs.
+    Emulated,
+}
+
+/// Values of the RTC registers (0x08-0x0C).
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+struct RtcRegisters {
+    seconds: u8,
+    minutes: u8,
+    hours: u8,
+    days: u16,
+    halted: bool,
+    day_carry: bool,
+}
+
+impl RtcRegisters {
+    fn read(&self, register: u8) -> u8 {
+        match register {
+            0x08 => self.seconds,
+            0x09 => self.minutes,
+            0x0A => self.hours,
+            0x0B => self.days as u8,
+            0x0C => ((self.days >> 8) as u8 & DAY_HIGH_MASK)
+                | if self.halted { HALT_MASK } else { 0 }
+                | if self.day_carry { DAY_CARRY_MASK } else { 0 },
+            _ => 0xFF,
+        }
+    }
+
+    fn write(&mut self, register: u8, value: u8) {
+        match register {
+            0x08 => self.seconds = value & 0x3F,
+            0x09 => self.minutes = value & 0x3F,
+            0x0A => self.hours = value & 0x1F,
+            0x0B => self.days = (self.days & 0x100) | value as u16,
+            0x0C => {
+                self.days = (self.days & 0xFF) | (((value & DAY_HIGH_MASK) as u16) << 8);
+                self.halted = value & HALT_MASK != 0;
+                self.day_carry = value & DAY_CARRY_MASK != 0;
+            }
+            _ => (),
+        }
+    }
+
+    /// Whether every counter is within its normal range, which allows advancing them arithmetically.
+    fn in_range(&self) -> bool {
+        self.seconds < 60 && self.minutes < 60 && self.hours < 24
+    }
+
+    /// Advances the counters by one second.
+    ///
+    /// Counters written with out of range values keep counting up to the limit of their bits
+    /// before wrapping to 0 without carrying into the next counter.
+    fn tick_second(&mut self) {
+        self.seconds = (self.seconds + 1) & 0x3F;
+        if self.seconds != 60 {
+            return;
+        }
+        self.seconds = 0;
+        self.minutes = (self.minutes + 1) & 0x3F;
+        if self.minutes != 60 {
+            return;
+        }
+        self.minutes = 0;
+        self.hours = (self.hours + 1) & 0x1F;
+        if self.hours != 24 {
+            return;
+        }
+        self.hours = 0;
+        self.advance_days(1);
+    }
+
+    fn advance_days(&mut self, days: u64) {
+        let days = self.days as u64 + days;
+        if days >= 512 {
+            self.day_carry = true;
+        }
+        self.days = (days % 512) as u16;
+    }
+
+    fn advance(&mut self, mut seconds: u64) {
+        while seconds > 0 && !self.in_range() {
+            self.tick_second();
+            seconds -= 1;
+        }
+        if seconds == 0 {
+            return;
+        }
+        let time = self.seconds as u64 + self.minutes as u64 * 60 + self.hours as u64 * 3600 + seconds;
+        self.seconds = (time % 60) as u8;
+        self.minutes = (time / 60 % 60) as u8;
+        self.hours = (time / 3600 % 24) as u8;
+        self.advance_days(time / 86400);
+    }
+
+    fn serialize(&self, data: &mut Vec<u8>) {
+        for register in 0x08..=0x0C {
+            data.extend_from_slice(&(self.read(register) as u32).to_le_bytes());
+        }
+    }
+
+    fn deserialize(data: &[u8]) -> Self {
+        let mut registers = RtcRegisters::default();
+        for (register, value) in (0x08..=0x0C).zip(data.chunks_exact(4)) {
+            registers.write(register, value[0]);
+        }
+        registers
+    }
+}
+
+/// Real-time clock of MBC3 cartridges.
+///
+/// The clock counts seconds, minutes, hours and a 9-bit day counter whose overflow sets the day
+/// carry bit, until the halt bit is set. Writing 0x00 then 0x01 to 0x6000-0x7FFF latches the
+/// counters into the registers read by the CPU.
+pub(crate) struct Rtc {
+    clock: RtcClock,
+    registers: RtcRegisters,
+    latched: RtcRegisters,
+    latch_armed: bool,
+    /// Wall clock time the registers were last brought up to date with, for the system clock.
+    last_update: SystemTime,
+    /// T-cycles elapsed since the last second, for the emulated clock.
+    pending_cycles: u32,
+}
+
+impl Rtc {
+    pub(crate) fn new(clock: RtcClock) -> Self {
+        Rtc {
+            clock,
+            registers: RtcRegisters::default(),
+            latched: RtcRegisters::default(),
+            latch_armed: false,
+            last_update: SystemTime::now(),
+            pending_cycles: 0,
+        }
+    }
+
+    pub(crate) fn read(&self, register: u8) -> u8 {
+        self.latched.read(register)
+    }
+
+    pub(crate) fn write(&mut self, register: u8, value: u8) {
+        self.update();
+        if register == 0x08 {
+            // Writing the seconds resets the sub-second counter.
+            self.last_update = SystemTime::now();
+            self.pending_cycles = 0;
+        }
+        self.registers.write(register, value);
+    }
+
+    pub(crate) fn write_latch(&mut self, value: u8) {
+        if self.latch_armed && value == 0x01 {
+            self.update();
+            self.latched = self.registers;
+        }
+        self.latch_armed = value == 0x00;
+    }
+
+    pub(crate) fn tick(&mut self, cycles: u8) {
+        if self.clock != RtcClock::Emulated || self.registers.halted {
+            return;
+        }
+        self.pending_cycles += cycles as u32;
+        if self.pending_cycles >= CLOCK_SPEED {
+            self.pending_cycles -= CLOCK_SPEED;
+            self.registers.tick_second();
+        }
+    }
+
+    /// Brings the registers up to date with the wall clock.
+    fn update(&mut self) {
+        if self.clock != RtcClock::System {
+            return;
+        }
+        let now = SystemTime::now();
+        let elapsed = now.duration_since(self.last_update).unwrap_or_default();
+        if self.registers.halted {
+            self.last_update = now;
+        } else {
+            self.registers.advance(elapsed.as_secs());
+            self.last_update += Duration::from_secs(elapsed.as_secs());
+        }
+    }
+
+    /// Appends the RTC trailer to `data`.
+    pub(crate) fn save(&mut self, data: &mut Vec<u8>) {
+        self.update();
+        self.registers.serialize(data);
+        self.latched.serialize(data);
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        data.extend_from_slice(&timestamp.to_le_bytes());
+    }
+
+    /// Restores the RTC from a trailer, advancing the system clock by the time elapsed since the save.
+    pub(crate) fn load(&mut self, trailer: &[u8]) -> Result<(), String> {
+        let timestamp = match trailer.len() {
+            RTC_TRAILER_SIZE => u64::from_le_bytes(trailer[40..48].try_into().unwrap()),
+            RTC_TRAILER_SIZE_32 => u32::from_le_bytes(trailer[40..44].try_into().unwrap()) as u64,
+            size => return Err(format!("Invalid RTC data size: {} bytes", size)),
+        };
+        self.registers = RtcRegisters::deserialize(&trailer[0..20]);
+        self.latched = RtcRegisters::deserialize(&trailer[20..40]);
+        self.last_update = SystemTime::now();
+        self.pending_cycles = 0;
+        if self.clock == RtcClock::System && !self.registers.halted {
+            let now = self.last_update.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+            self.registers.advance(now.saturating_sub(timestamp));
+        }
+        Ok(())
+    }
+}
+
+impl Snapshot for RtcRegisters {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.seconds);
+        state.write_u8(self.minutes);
+        state.write_u8(self.hours);
+        state.write_u16(self.days);
+        state.write_bool(self.halted);
+        state.write_bool(self.day_carry);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.seconds = state.read_u8()?;
+        self.minutes = state.read_u8()?;
+        self.hours = state.read_u8()?;
+        self.days = state.read_u16()?;
+        self.halted = state.read_bool()?;
+        self.day_carry = state.read_bool()?;
+        Ok(())
+    }
+}
+
+/// With the system clock, the time of the last update is saved as a UNIX timestamp, so that the
+/// clock catches up with the time elapsed since the save once the state is loaded.
+impl Snapshot for Rtc {
+    fn save_state(&self, state: &mut StateWriter) {
+        self.registers.save_state(state);
+        self.latched.save_state(state);
+        state.write_bool(self.latch_armed);
+        let last_update = self.last_update.duration_since(UNIX_EPOCH).unwrap_or_default();
+        state.write_u64(last_update.as_secs());
+        state.write_u32(last_update.subsec_nanos());
+        state.write_u32(self.pending_cycles);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.registers.load_state(state)?;
+        self.latched.load_state(state)?;
+        self.latch_armed = state.read_bool()?;
+        let seconds = state.read_u64()?;
+        let nanos = state.read_u32()?;
+        self.last_update = UNIX_EPOCH + Duration::new(seconds, nanos);
+        self.pending_cycles = state.read_u32()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick_seconds(rtc: &mut Rtc, seconds: u32) {
+        for _ in 0..seconds {
+            let mut cycles = CLOCK_SPEED;
+            while cycles > 0 {
+                let step = cycles.min(u8::MAX as u32);
+                rtc.tick(step as u8);
+                cycles -= step;
+            }
+        }
+    }
+
+    fn latch(rtc: &mut Rtc) {
+        rtc.write_latch(0x00);
+        rtc.write_latch(0x01);
+    }
+
+    /// RTC set to 23:59:59 on `days`.
+    fn rtc_before_midnight(days: u16) -> Rtc {
+        let mut rtc = Rtc::new(RtcClock::Emulated);
+        rtc.write(0x08, 59);
+        rtc.write(0x09, 59);
+        rtc.write(0x0A, 23);
+        rtc.write(0x0B, days as u8);
+        rtc.write(0x0C, (days >> 8) as u8);
+        rtc
+    }
+
+    #[test]
+    fn registers_are_latched_by_writing_0_then_1() {
+        let mut rtc = Rtc::new(RtcClock::Emulated);
+        rtc.write(0x08, 5);
+        assert_eq!(rtc.read(0x08), 0);
+
+        rtc.write_latch(0x01);
+        assert_eq!(rtc.read(0x08), 0);
+        rtc.write_latch(0x00);
+        rtc.write_latch(0x02);
+        rtc.write_latch(0x01);
+        assert_eq!(rtc.read(0x08), 0);
+
+        latch(&mut rtc);
+        assert_eq!(rtc.read(0x08), 5);
+        rtc.write(0x08, 6);
+        assert_eq!(rtc.read(0x08), 5);
+    }
+
+    #[test]
+    fn seconds_carry_into_the_days() {
+        let mut rtc = rtc_before_midnight(0x0FF);
+        tick_seconds(&mut rtc, 1);
+        latch(&mut rtc);
+        assert_eq!([rtc.read(0x08), rtc.read(0x09), rtc.read(0x0A)], [0, 0, 0]);
+        assert_eq!(rtc.read(0x0B), 0x00);
+        assert_eq!(rtc.read(0x0C), DAY_HIGH_MASK);
+    }
+
+    #[test]
+    fn day_counter_overflow_sets_the_carry_until_cleared() {
+        let mut rtc = rtc_before_midnight(511);
+        tick_seconds(&mut rtc, 1);
+        latch(&mut rtc);
+        assert_eq!(rtc.read(0x0B), 0);
+        assert_eq!(rtc.read(0x0C), DAY_CARRY_MASK);
+
+        tick_seconds(&mut rtc, 1);
+        latch(&mut rtc);
+        assert_eq!(rtc.read(0x0C), DAY_CARRY_MASK);
+        rtc.write(0x0C, 0);
+        latch(&mut rtc);
+        assert_eq!(rtc.read(0x0C), 0);
+    }
+
+    #[test]
+    fn halt_bit_stops_the_clock() {
+        let mut rtc = Rtc::new(RtcClock::Emulated);
+        rtc.write(0x0C, HALT_MASK);
+        tick_seconds(&mut rtc, 2);
+        latch(&mut rtc);
+        assert_eq!(rtc.read(0x08), 0);
+        assert_eq!(rtc.read(0x0C), HALT_MASK);
+
+        rtc.write(0x0C, 0);
+        tick_seconds(&mut rtc, 2);
+        latch(&mut rtc);
+        assert_eq!(rtc.read(0x08), 2);
+    }
+
+    #[test]
+    fn save_trailer_round_trips() {
+        let mut rtc = rtc_before_midnight(0x1A5);
+        latch(&mut rtc);
+        rtc.write(0x08, 12);
+        let mut trailer = Vec::new();
+        rtc.save(&mut trailer);
+        assert_eq!(trailer.len(), RTC_TRAILER_SIZE);
+
+        let mut loaded = Rtc::new(RtcClock::Emulated);
+        loaded.load(&trailer).unwrap();
+        assert_eq!(loaded.registers, rtc.registers);
+        assert_eq!(loaded.latched, rtc.latched);
+
+        let mut legacy = Rtc::new(RtcClock::Emulated);
+        legacy.load(&trailer[..RTC_TRAILER_SIZE_32]).unwrap();
+        assert_eq!(legacy.registers, rtc.registers);
+        assert!(Rtc::new(RtcClock::Emulated).load(&trailer[..40]).is_err());
+    }
+}
@@ -1,44 +1,111 @@
+use crate::apu::APU;
+use crate::checksum;
+use crate::clock::Clocked;
+use crate::interrupts::Interrupts;
+use crate::joypad::{Button, Joypad};
+use crate::mbc;
+use crate::mbc::{Mapper, RomOnly, RtcClock};
+use crate::ppu::PPU;
 use crate::rom::Rom;
+use crate::save_state::{Snapshot, StateReader, StateWriter};
+use crate::serial::{Disconnected, Serial, SerialEndpoint};
+use crate::timer::Timer;
 use crate::types::Memory;
 use crate::types::MemorySection;
+use crate::watchpoints::{Access, Hit, Watchpoint, Watchpoints};
 
 pub struct MMU {
-    rom: Memory,
-    vram: Memory,
-    external_ram: Memory,
+    pub(crate) cartridge: Box<dyn Mapper>,
     internal_ram: Memory,
-    oam: Memory,
     io_ports: Memory,
     hram: Memory,
+    pub(crate) interrupts: Interrupts,
+    pub(crate) timer: Timer,
+    pub(crate) ppu: PPU,
+    pub(crate) apu: APU,
+    pub(crate) joypad: Joypad,
+    pub(crate) serial: Serial,
+    /// T-cycles elapsed since power on.
+    cycles: u64,
+    /// Only set while watchpoints are, so that accesses are not slowed down otherwise.
+    watchpoints: Option<Watchpoints>,
+    /// Whether LY reads as 0x90, as Gameboy Doctor expects.
+    stub_ly: bool,
+    /// CRC-32 of the ROM, checked when loading a save state.
+    rom_checksum: u32,
 }
 
 impl MMU {
     pub fn new() -> MMU {
         MMU {
-            rom: Memory { data: vec![0; MemorySection::Rom.size()] },
-            vram: Memory { data: vec![0; MemorySection::VRam.size()] },
-            external_ram: Memory { data: vec![0; MemorySection::ExternalRam.size()] },
+            cartridge: Box::new(RomOnly::new(Memory { data: vec![0; MemorySection::Rom.size()] }, 0, false)),
             internal_ram: Memory { data: vec![0; MemorySection::InternalRam.size()] },
-            oam: Memory { data: vec![0; MemorySection::Oam.size()] },
             io_ports: Memory { data: vec![0; MemorySection::IoPorts.size()] },
             hram: Memory { data: vec![0; MemorySection::HRam.size()] },
+            interrupts: Interrupts::new(),
+            timer: Timer::new(),
+            ppu: PPU::new(),
+            apu: APU::new(),
+            joypad: Joypad::new(),
+            serial: Serial::new(Box::new(Disconnected)),
+            cycles: 0,
+            watchpoints: None,
+            stub_ly: false,
+            rom_checksum: checksum::crc32(&[]),
         }
     }
 
-    pub(crate) fn with_rom(self, rom: Rom) -> Self {
+    /// Inserts the cartridge, failing if its memory bank controller is not supported.
+    pub(crate) fn with_rom(self, rom: Rom, rtc_clock: RtcClock) -> Result<Self, String> {
+        Ok(MMU {
+            rom_checksum: checksum::crc32(&rom.memory.data),
+            cartridge: mbc::from_rom(rom, rtc_clock)?,
+            ..self
+        })
+    }
+
+    /// Plugs `endpoint` into the serial port.
+    pub(crate) fn with_serial_endpoint(self, endpoint: Box<dyn SerialEndpoint>) -> Self {
         MMU {
-            rom: rom.memory,
+            serial: Serial::new(endpoint),
+            ..self
+        }
+    }
+
+    /// Makes LY read as 0x90, the first line of VBlank, so that traces compare with the logs of
+    /// Gameboy Doctor regardless of the PPU timings.
+    pub(crate) fn with_stubbed_ly(self) -> Self {
+        MMU {
+            stub_ly: true,
             ..self
         }
     }
 
     pub fn read_byte(&self, addr: u16) -> u8 {
+        let value = self.peek_byte(addr);
+        if let Some(watchpoints) = &self.watchpoints {
+            watchpoints.check(Access::Read, addr, value, value);
+        }
+        value
+    }
+
+    /// Reads a byte without triggering watchpoints, for tools inspecting memory.
+    pub(crate) fn peek_byte(&self, addr: u16) -> u8 {
         match addr {
-            _ if MemorySection::Rom.contains(addr) => self.rom.data[addr as usize],
-            _ if MemorySection::VRam.contains(addr) => self.vram.data[(addr - 0x8000) as usize],
-            _ if MemorySection::ExternalRam.contains(addr) => self.external_ram.data[(addr - 0xA000) as usize],
+            PPU::LY_ADDRESS if self.stub_ly => 0x90,
+            Joypad::P1_ADDRESS => self.joypad.read_byte(),
+            Serial::SB_ADDRESS | Serial::SC_ADDRESS => self.serial.read_byte(addr),
+            Interrupts::IF_ADDRESS => self.interrupts.read_flag(),
+            Interrupts::IE_ADDRESS => self.interrupts.read_enable(),
+            Timer::DIV_ADDRESS..=Timer::TAC_ADDRESS => self.timer.read_byte(addr),
+            PPU::DMA_ADDRESS => self.io_ports.data[(addr - 0xFF00) as usize],
+            PPU::LCDC_ADDRESS..=PPU::WX_ADDRESS => self.ppu.read_byte(addr),
+            APU::NR10_ADDRESS..=APU::WAVE_RAM_END => self.apu.read_byte(addr),
+            _ if MemorySection::Rom.contains(addr) => self.cartridge.read_rom(addr),
+            _ if MemorySection::VRam.contains(addr) => self.ppu.read_vram(addr),
+            _ if MemorySection::ExternalRam.contains(addr) => self.cartridge.read_ram(addr),
             _ if MemorySection::InternalRam.contains(addr) => self.internal_ram.data[(addr - 0xC000) as usize],
-            _ if MemorySection::Oam.contains(addr) => self.oam.data[(addr - 0xFE00) as usize],
+            _ if MemorySection::Oam.contains(addr) => self.ppu.read_oam(addr),
             _ if MemorySection::IoPorts.contains(addr) => self.io_ports.data[(addr - 0xFF00) as usize],
             _ if MemorySection::HRam.contains(addr) => self.hram.data[(addr - 0xFF80) as usize],
             _ => 0,
@@ -52,12 +119,31 @@ impl MMU {
     }
 
     pub fn write_byte(&mut self, addr: u16, value: u8) {
+        if let Some(watchpoints) = &self.watchpoints {
+            watchpoints.check(Access::Write, addr, value, self.peek_byte(addr));
+        }
+        self.poke_byte(addr, value);
+    }
+
+    /// Writes a byte without triggering watchpoints, for tools modifying memory.
+    pub(crate) fn poke_byte(&mut self, addr: u16, value: u8) {
         match addr {
-            _ if MemorySection::Rom.contains(addr) => self.rom.data[addr as usize] = value,
-            _ if MemorySection::VRam.contains(addr) => self.vram.data[(addr - 0x8000) as usize] = value,
-            _ if MemorySection::ExternalRam.contains(addr) => self.external_ram.data[(addr - 0xA000) as usize] = value,
+            Joypad::P1_ADDRESS => self.joypad.write_byte(value, &mut self.interrupts),
+            Serial::SB_ADDRESS | Serial::SC_ADDRESS => self.serial.write_byte(addr, value),
+            Interrupts::IF_ADDRESS => self.interrupts.write_flag(value),
+            Interrupts::IE_ADDRESS => self.interrupts.write_enable(value),
+            Timer::DIV_ADDRESS..=Timer::TAC_ADDRESS => self.timer.write_byte(addr, value),
+            PPU::DMA_ADDRESS => {
+                self.io_ports.data[(addr - 0xFF00) as usize] = value;
+                self.oam_dma(value);
+            }
+            PPU::LCDC_ADDRESS..=PPU::WX_ADDRESS => self.ppu.write_byte(addr, value, &mut self.interrupts),
+            APU::NR10_ADDRESS..=APU::WAVE_RAM_END => self.apu.write_byte(addr, value),
+            _ if MemorySection::Rom.contains(addr) => self.cartridge.write_rom(addr, value),
+            _ if MemorySection::VRam.contains(addr) => self.ppu.write_vram(addr, value),
+            _ if MemorySection::ExternalRam.contains(addr) => self.cartridge.write_ram(addr, value),
             _ if MemorySection::InternalRam.contains(addr) => self.internal_ram.data[(addr - 0xC000) as usize] = value,
-            _ if MemorySection::Oam.contains(addr) => self.oam.data[(addr - 0xFE00) as usize] = value,
+            _ if MemorySection::Oam.contains(addr) => self.ppu.write_oam(addr, value),
             _ if MemorySection::IoPorts.contains(addr) => self.io_ports.data[(addr - 0xFF00) as usize] = value,
             _ if MemorySection::HRam.contains(addr) => self.hram.data[(addr - 0xFF80) as usize] = value,
             _ => (),
@@ -70,4 +156,96 @@ impl MMU {
         self.write_byte(addr, low);
         self.write_byte(addr.wrapping_add(1), high);
     }
-}
\ No newline at end of file
+
+    pub fn press_button(&mut self, button: Button) {
+        self.joypad.press(button, &mut self.interrupts);
+    }
+
+    pub fn release_button(&mut self, button: Button) {
+        self.joypad.release(button);
+    }
+
+    /// Copies 160 bytes from `source * 0x100` to OAM.
+    ///
+    /// The transfer is performed at once instead of over 160 M-cycles.
+    fn oam_dma(&mut self, source: u8) {
+        let start = (source as u16) << 8;
+        let (oam_start, oam_end) = MemorySection::Oam.range();
+        for offset in 0..=(oam_end - oam_start) {
+            let value = self.read_byte(start + offset);
+            self.ppu.write_oam(oam_start + offset, value);
+        }
+    }
+
+    pub(crate) fn watchpoints(&self) -> &[Watchpoint] {
+        self.watchpoints.as_ref().map_or(&[], |watchpoints| watchpoints.list())
+    }
+
+    pub(crate) fn add_watchpoint(&mut self, watchpoint: Watchpoint) {
+        self.watchpoints.get_or_insert_with(Watchpoints::new).add(watchpoint);
+    }
+
+    pub(crate) fn remove_watchpoint(&mut self, index: usize) -> Option<Watchpoint> {
+        let watchpoints = self.watchpoints.as_mut()?;
+        let removed = watchpoints.remove(index);
+        if watchpoints.list().is_empty() {
+            self.watchpoints = None;
+        }
+        removed
+    }
+
+    /// Accesses that triggered watchpoints since the last call.
+    pub(crate) fn take_watchpoint_hits(&mut self) -> Vec<Hit> {
+        self.watchpoints.as_mut().map_or_else(Vec::new, |watchpoints| watchpoints.take_hits())
+    }
+
+    /// T-cycles elapsed since power on.
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    pub(crate) fn rom_checksum(&self) -> u32 {
+        self.rom_checksum
+    }
+
+    /// Advances the components clocked alongside the CPU by the T-cycles of a CPU step.
+    pub fn tick(&mut self, cycles: u8) {
+        self.cycles += cycles as u64;
+        let components: [&mut dyn Clocked; 4] = [&mut self.timer, &mut self.serial, &mut self.ppu, &mut self.apu];
+        for component in components {
+            component.tick(cycles, &mut self.interrupts);
+        }
+        self.cartridge.tick(cycles);
+    }
+}
+
+/// The watchpoints and the options of the MMU are not part of the state of the machine.
+impl Snapshot for MMU {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u64(self.cycles);
+        state.write_memory(&self.internal_ram);
+        state.write_memory(&self.io_ports);
+        state.write_memory(&self.hram);
+        self.interrupts.save_state(state);
+        self.timer.save_state(state);
+        self.ppu.save_state(state);
+        self.apu.save_state(state);
+        self.joypad.save_state(state);
+        self.serial.save_state(state);
+        self.cartridge.save_state(state);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.cycles = state.read_u64()?;
+        state.read_memory(&mut self.internal_ram)?;
+        state.read_memory(&mut self.io_ports)?;
+        state.read_memory(&mut self.hram)?;
+        self.interrupts.load_state(state)?;
+        self.timer.load_state(state)?;
+        self.ppu.load_state(state)?;
+        self.apu.load_state(state)?;
+        self.joypad.load_state(state)?;
+        self.serial.load_state(state)?;
+        self.cartridge.load_state(state)
+    }
+}
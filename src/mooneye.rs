@@ -0,0 +1,174 @@
+//! Runner of the Mooneye test ROMs.
+//!
+//! The ROMs report their result by executing `LD B, B` with the Fibonacci numbers 3, 5, 8, 13,
+//! 21 and 34 in B, C, D, E, H and L when they pass, and with 0x42 in all of them when they fail.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::clock::CLOCK_SPEED;
+use crate::cpu::CPU;
+use crate::mbc::RtcClock;
+use crate::mmu::MMU;
+use crate::rom::Rom;
+
+/// Opcode of `LD B, B`, used by the ROMs as a breakpoint.
+const BREAKPOINT_OPCODE: u8 = 0x40;
+
+const PASS_SIGNATURE: [u8; 6] = [3, 5, 8, 13, 21, 34];
+const FAIL_SIGNATURE: [u8; 6] = [0x42; 6];
+
+/// Result of a test ROM.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Outcome {
+    Passed,
+    Failed,
+    /// The ROM did not report a result within the time limit.
+    Timeout,
+    /// The ROM could not be run.
+    Error(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Passed => write!(f, "passed"),
+            Outcome::Failed => write!(f, "FAILED"),
+            Outcome::Timeout => write!(f, "TIMEOUT"),
+            Outcome::Error(err) => write!(f, "ERROR: {}", err),
+        }
+    }
+}
+
+/// Runs the ROM at `path` for at most `timeout` emulated seconds.
+fn run_rom(path: &Path, timeout: u32) -> Outcome {
+    let rom = match Rom::from_path(&path.to_string_lossy()) {
+        Ok(rom) if rom.validate() => rom,
+        Ok(_) => return Outcome::Error(String::from("Invalid ROM")),
+        Err(err) => return Outcome::Error(err.to_string()),
+    };
+    let mut mmu = match MMU::new().with_rom(rom, RtcClock::Emulated) {
+        Ok(mmu) => mmu,
+        Err(err) => return Outcome::Error(err),
+    };
+    run_machine(&mut CPU::new(), &mut mmu, timeout)
+}
+
+/// Runs the machine until it executes `LD B, B` with a signature in its registers, for at most
+/// `timeout` emulated seconds.
+fn run_machine(cpu: &mut CPU, mmu: &mut MMU, timeout: u32) -> Outcome {
+    let budget = timeout as u64 * CLOCK_SPEED as u64;
+    while mmu.cycles() < budget {
+        let registers = cpu.registers();
+        if mmu.peek_byte(registers.pc) == BREAKPOINT_OPCODE {
+            let signature = [registers.b, registers.c, registers.d, registers.e, registers.h, registers.l];
+            if signature == PASS_SIGNATURE {
+                return Outcome::Passed;
+            }
+            if signature == FAIL_SIGNATURE {
+                return Outcome::Failed;
+            }
+        }
+        cpu.step(mmu);
+    }
+    Outcome::Timeout
+}
+
+/// ROMs (.gb) at `path`, searched recursively when it is a directory.
+fn collect_roms(path: &Path) -> io::Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut roms = Vec::new();
+    for entry in fs::read_dir(path)? {
+        let entry_path = entry?.path();
+        if entry_path.is_dir() {
+            roms.extend(collect_roms(&entry_path)?);
+        } else if entry_path.extension().is_some_and(|extension| extension == "gb") {
+            roms.push(entry_path);
+        }
+    }
+    roms.sort();
+    Ok(roms)
+}
+
+/// Runs the ROMs at `path` and prints their results followed by a summary.
+///
+/// Returns whether every ROM passed.
+pub(crate) fn run(path: &Path, timeout: u32) -> Result<bool, String> {
+    let roms = collect_roms(path).map_err(|err| format!("Failed to list ROMs in {}: {}", path.display(), err))?;
+    if roms.is_empty() {
+        return Err(format!("No ROM found in {}", path.display()));
+    }
+    let names: Vec<String> = roms.iter()
+        .map(|rom| match rom.strip_prefix(path) {
+            Ok(relative) if path.is_dir() => relative.display().to_string(),
+            _ => rom.display().to_string(),
+        })
+        .collect();
+    let width = names.iter().map(|name| name.len()).max().unwrap_or_default();
+
+    let mut outcomes = Vec::new();
+    for (rom, name) in roms.iter().zip(&names) {
+        let outcome = run_rom(rom, timeout);
+        println!("{:<width$}  {}", name, outcome);
+        outcomes.push(outcome);
+    }
+
+    let count = |expected: fn(&Outcome) -> bool| outcomes.iter().filter(|outcome| expected(outcome)).count();
+    let passed = count(|outcome| *outcome == Outcome::Passed);
+    println!();
+    println!("{:<10} {:>5}", "Passed", passed);
+    println!("{:<10} {:>5}", "Failed", count(|outcome| *outcome == Outcome::Failed));
+    println!("{:<10} {:>5}", "Timeout", count(|outcome| *outcome == Outcome::Timeout));
+    println!("{:<10} {:>5}", "Error", count(|outcome| matches!(outcome, Outcome::Error(_))));
+    println!("{:<10} {:>5}", "Total", outcomes.len());
+    Ok(passed == outcomes.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::machine;
+
+    fn run_program(program: &[u8]) -> Outcome {
+        let (mut cpu, mut mmu) = machine(program);
+        run_machine(&mut cpu, &mut mmu, 1)
+    }
+
+    #[test]
+    fn fibonacci_signature_passes() {
+        let program = [
+            0x40, // LD B,B without a signature
+            0x06, 3, // LD B,3
+            0x0E, 5, // LD C,5
+            0x16, 8, // LD D,8
+            0x1E, 13, // LD E,13
+            0x26, 21, // LD H,21
+            0x2E, 34, // LD L,34
+            0x40, // LD B,B
+        ];
+        assert_eq!(run_program(&program), Outcome::Passed);
+    }
+
+    #[test]
+    fn signature_of_0x42_fails() {
+        let program = [
+            0x06, 0x42, // LD B,0x42
+            0x48, // LD C,B
+            0x50, // LD D,B
+            0x58, // LD E,B
+            0x60, // LD H,B
+            0x68, // LD L,B
+            0x40, // LD B,B
+        ];
+        assert_eq!(run_program(&program), Outcome::Failed);
+    }
+
+    #[test]
+    fn rom_without_signature_times_out() {
+        assert_eq!(run_program(&[0x18, 0xFE]), Outcome::Timeout); // JR -2
+    }
+}
@@ -0,0 +1,518 @@
+use crate::clock::Clocked;
+use crate::interrupts::{Interrupt, Interrupts};
+use crate::save_state::{Snapshot, StateReader, StateWriter};
+use crate::types::{Memory, MemorySection};
+
+pub(crate) const SCREEN_WIDTH: usize = 160;
+pub(crate) const SCREEN_HEIGHT: usize = 144;
+
+/// Dots (T-cycles) per scanline, and the length of modes 2 and 3 within a line.
+const DOTS_PER_LINE: u16 = 456;
+const OAM_SCAN_DOTS: u16 = 80;
+const DRAWING_DOTS: u16 = 172;
+
+/// Number of lines per frame, including the 10 VBlank lines.
+const LINES_PER_FRAME: u8 = 154;
+
+const MAX_SPRITES_PER_LINE: usize = 10;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Mode {
+    HBlank = 0,
+    VBlank = 1,
+    OamScan = 2,
+    Drawing = 3,
+}
+
+/// LCDC (0xFF40) bits.
+mod lcdc {
+    pub(super) const BG_WINDOW_ENABLE: u8 = 0b0000_0001;
+    pub(super) const OBJ_ENABLE: u8 = 0b0000_0010;
+    pub(super) const OBJ_SIZE: u8 = 0b0000_0100;
+    pub(super) const BG_TILE_MAP: u8 = 0b0000_1000;
+    pub(super) const TILE_DATA: u8 = 0b0001_0000;
+    pub(super) const WINDOW_ENABLE: u8 = 0b0010_0000;
+    pub(super) const WINDOW_TILE_MAP: u8 = 0b0100_0000;
+    pub(super) const LCD_ENABLE: u8 = 0b1000_0000;
+}
+
+/// STAT (0xFF41) bits.
+mod stat {
+    pub(super) const COINCIDENCE: u8 = 0b0000_0100;
+    pub(super) const HBLANK_INTERRUPT: u8 = 0b0000_1000;
+    pub(super) const VBLANK_INTERRUPT: u8 = 0b0001_0000;
+    pub(super) const OAM_INTERRUPT: u8 = 0b0010_0000;
+    pub(super) const COINCIDENCE_INTERRUPT: u8 = 0b0100_0000;
+    pub(super) const WRITABLE: u8 = 0b0111_1000;
+}
+
+/// Sprite attribute flags (OAM byte 3).
+mod attributes {
+    pub(super) const PALETTE: u8 = 0b0001_0000;
+    pub(super) const X_FLIP: u8 = 0b0010_0000;
+    pub(super) const Y_FLIP: u8 = 0b0100_0000;
+    pub(super) const BG_PRIORITY: u8 = 0b1000_0000;
+}
+
+#[derive(Copy, Clone)]
+struct Sprite {
+    y: i16,
+    x: i16,
+    tile: u8,
+    attributes: u8,
+}
+
+/// Pixel Processing Unit.
+///
+/// Walks the OAM scan (2), drawing (3), HBlank (0) and VBlank (1) modes dot by dot, renders each
+/// visible scanline into a 160x144 framebuffer of shades (0 being white and 3 black) once
+/// drawing is over, and raises the VBlank and STAT interrupts.
+pub(crate) struct PPU {
+    vram: Memory,
+    oam: Memory,
+    lcdc: u8,
+    stat: u8,
+    scy: u8,
+    scx: u8,
+    ly: u8,
+    lyc: u8,
+    bgp: u8,
+    obp0: u8,
+    obp1: u8,
+    wy: u8,
+    wx: u8,
+    mode: Mode,
+    dots: u16,
+    /// Internal line counter of the window, only incremented on lines where it is drawn.
+    window_line: u8,
+    /// STAT interrupt line, the interrupt being requested on its rising edge only.
+    stat_line: bool,
+    framebuffer: Vec<u8>,
+    frame_ready: bool,
+}
+
+impl PPU {
+    pub(crate) const LCDC_ADDRESS: u16 = 0xFF40;
+    pub(crate) const STAT_ADDRESS: u16 = 0xFF41;
+    pub(crate) const SCY_ADDRESS: u16 = 0xFF42;
+    pub(crate) const SCX_ADDRESS: u16 = 0xFF43;
+    pub(crate) const LY_ADDRESS: u16 = 0xFF44;
+    pub(crate) const LYC_ADDRESS: u16 = 0xFF45;
+    pub(crate) const DMA_ADDRESS: u16 = 0xFF46;
+    pub(crate) const BGP_ADDRESS: u16 = 0xFF47;
+    pub(crate) const OBP0_ADDRESS: u16 = 0xFF48;
+    pub(crate) const OBP1_ADDRESS: u16 = 0xFF49;
+    pub(crate) const WY_ADDRESS: u16 = 0xFF4A;
+    pub(crate) const WX_ADDRESS: u16 = 0xFF4B;
+
+    /// PPU as left by the DMG boot ROM, which is not emulated: LCD on, background enabled and
+    /// the 0xFC palette.
+    pub(crate) fn new() -> Self {
+        PPU {
+            vram: Memory { data: vec![0; MemorySection::VRam.size()] },
+            oam: Memory { data: vec![0; MemorySection::Oam.size()] },
+            lcdc: 0x91,
+            stat: 0,
+            scy: 0,
+            scx: 0,
+            ly: 0,
+            lyc: 0,
+            bgp: 0xFC,
+            obp0: 0,
+            obp1: 0,
+            wy: 0,
+            wx: 0,
+            mode: Mode::OamScan,
+            dots: 0,
+            window_line: 0,
+            stat_line: false,
+            framebuffer: vec![0; SCREEN_WIDTH * SCREEN_HEIGHT],
+            frame_ready: false,
+        }
+    }
+
+    pub(crate) fn read_vram(&self, addr: u16) -> u8 {
+        self.vram.data[(addr - 0x8000) as usize]
+    }
+
+    pub(crate) fn write_vram(&mut self, addr: u16, value: u8) {
+        self.vram.data[(addr - 0x8000) as usize] = value;
+    }
+
+    pub(crate) fn read_oam(&self, addr: u16) -> u8 {
+        self.oam.data[(addr - 0xFE00) as usize]
+    }
+
+    pub(crate) fn write_oam(&mut self, addr: u16, value: u8) {
+        self.oam.data[(addr - 0xFE00) as usize] = value;
+    }
+
+    pub(crate) fn read_byte(&self, addr: u16) -> u8 {
+        match addr {
+            Self::LCDC_ADDRESS => self.lcdc,
+            Self::STAT_ADDRESS => self.read_stat(),
+            Self::SCY_ADDRESS => self.scy,
+            Self::SCX_ADDRESS => self.scx,
+            Self::LY_ADDRESS => self.ly,
+            Self::LYC_ADDRESS => self.lyc,
+            Self::BGP_ADDRESS => self.bgp,
+            Self::OBP0_ADDRESS => self.obp0,
+            Self::OBP1_ADDRESS => self.obp1,
+            Self::WY_ADDRESS => self.wy,
+            Self::WX_ADDRESS => self.wx,
+            _ => 0xFF,
+        }
+    }
+
+    pub(crate) fn write_byte(&mut self, addr: u16, value: u8, interrupts: &mut Interrupts) {
+        match addr {
+            Self::LCDC_ADDRESS => self.write_lcdc(value),
+            Self::STAT_ADDRESS => self.stat = value & stat::WRITABLE,
+            Self::SCY_ADDRESS => self.scy = value,
+            Self::SCX_ADDRESS => self.scx = value,
+            Self::LY_ADDRESS => (),
+            Self::LYC_ADDRESS => self.lyc = value,
+            Self::BGP_ADDRESS => self.bgp = value,
+            Self::OBP0_ADDRESS => self.obp0 = value,
+            Self::OBP1_ADDRESS => self.obp1 = value,
+            Self::WY_ADDRESS => self.wy = value,
+            Self::WX_ADDRESS => self.wx = value,
+            _ => (),
+        }
+        self.update_stat_line(interrupts);
+    }
+
+    /// Returns true once per frame, when the PPU enters VBlank.
+    pub(crate) fn take_frame(&mut self) -> bool {
+        std::mem::take(&mut self.frame_ready)
+    }
+
+    /// Shades of the last rendered frame, row by row.
+    pub(crate) fn framebuffer(&self) -> &[u8] {
+        &self.framebuffer
+    }
+
+    fn tick_dot(&mut self, interrupts: &mut Interrupts) {
+        self.dots += 1;
+        match self.mode {
+            Mode::OamScan if self.dots == OAM_SCAN_DOTS => self.mode = Mode::Drawing,
+            Mode::Drawing if self.dots == OAM_SCAN_DOTS + DRAWING_DOTS => {
+                self.render_scanline();
+                self.mode = Mode::HBlank;
+            }
+            _ if self.dots == DOTS_PER_LINE => {
+                self.dots = 0;
+                self.ly += 1;
+                if self.ly == SCREEN_HEIGHT as u8 {
+                    self.mode = Mode::VBlank;
+                    self.frame_ready = true;
+                    interrupts.request(Interrupt::VBlank);
+                } else if self.ly == LINES_PER_FRAME {
+                    self.ly = 0;
+                    self.window_line = 0;
+                    self.mode = Mode::OamScan;
+                } else if self.mode != Mode::VBlank {
+                    self.mode = Mode::OamScan;
+                }
+            }
+            _ => return,
+        }
+        self.update_stat_line(interrupts);
+    }
+
+    fn lcd_enabled(&self) -> bool {
+        self.lcdc & lcdc::LCD_ENABLE != 0
+    }
+
+    fn write_lcdc(&mut self, value: u8) {
+        let was_enabled = self.lcd_enabled();
+        self.lcdc = value;
+        match (was_enabled, self.lcd_enabled()) {
+            (true, false) => {
+                self.ly = 0;
+                self.dots = 0;
+                self.window_line = 0;
+                self.mode = Mode::HBlank;
+            }
+            (false, true) => self.mode = Mode::OamScan,
+            _ => (),
+        }
+    }
+
+    fn read_stat(&self) -> u8 {
+        let mode = if self.lcd_enabled() { self.mode as u8 } else { 0 };
+        let coincidence = if self.ly == self.lyc { stat::COINCIDENCE } else { 0 };
+        0x80 | self.stat | coincidence | mode
+    }
+
+    fn update_stat_line(&mut self, interrupts: &mut Interrupts) {
+        let line = self.lcd_enabled() && (
+            (self.stat & stat::COINCIDENCE_INTERRUPT != 0 && self.ly == self.lyc)
+                || (self.stat & stat::HBLANK_INTERRUPT != 0 && self.mode == Mode::HBlank)
+                || (self.stat & stat::VBLANK_INTERRUPT != 0 && self.mode == Mode::VBlank)
+                || (self.stat & stat::OAM_INTERRUPT != 0 && self.mode == Mode::OamScan)
+        );
+        if line && !self.stat_line {
+            interrupts.request(Interrupt::LcdStat);
+        }
+        self.stat_line = line;
+    }
+
+    fn render_scanline(&mut self) {
+        let mut bg_colors = [0u8; SCREEN_WIDTH];
+        let row = self.ly as usize * SCREEN_WIDTH;
+        if self.lcdc & lcdc::BG_WINDOW_ENABLE != 0 {
+            self.render_background(&mut bg_colors);
+            for (x, &color) in bg_colors.iter().enumerate() {
+                self.framebuffer[row + x] = Self::shade(self.bgp, color);
+            }
+        } else {
+            // On DMG, the background and window are blank, white whatever the palette.
+            self.framebuffer[row..row + SCREEN_WIDTH].fill(0);
+        }
+        if self.lcdc & lcdc::OBJ_ENABLE != 0 {
+            self.render_sprites(&bg_colors);
+        }
+    }
+
+    fn render_background(&mut self, bg_colors: &mut [u8; SCREEN_WIDTH]) {
+        let window_x = self.wx as i16 - 7;
+        let window_visible = self.lcdc & lcdc::WINDOW_ENABLE != 0 && self.ly >= self.wy && self.wx <= 166;
+
+        for (x, color) in bg_colors.iter_mut().enumerate() {
+            *color = if window_visible && x as i16 >= window_x {
+                let map = if self.lcdc & lcdc::WINDOW_TILE_MAP != 0 { 0x9C00 } else { 0x9800 };
+                self.tile_map_color(map, (x as i16 - window_x) as u8, self.window_line)
+            } else {
+                let map = if self.lcdc & lcdc::BG_TILE_MAP != 0 { 0x9C00 } else { 0x9800 };
+                self.tile_map_color(map, (x as u8).wrapping_add(self.scx), self.ly.wrapping_add(self.scy))
+            };
+        }
+
+        if window_visible && window_x < SCREEN_WIDTH as i16 {
+            self.window_line += 1;
+        }
+    }
+
+    /// Color index of the pixel at (x, y) of the 256x256 background described by a tile map.
+    fn tile_map_color(&self, map: u16, x: u8, y: u8) -> u8 {
+        let tile_index = self.read_vram(map + (y as u16 / 8) * 32 + (x as u16 / 8));
+        let tile_address = if self.lcdc & lcdc::TILE_DATA != 0 {
+            0x8000 + tile_index as u16 * 16
+        } else {
+            (0x9000 + (tile_index as i8 as i32) * 16) as u16
+        };
+        self.tile_color(tile_address, x % 8, y % 8)
+    }
+
+    fn tile_color(&self, tile_address: u16, x: u8, y: u8) -> u8 {
+        let low = self.read_vram(tile_address + y as u16 * 2);
+        let high = self.read_vram(tile_address + y as u16 * 2 + 1);
+        let bit = 7 - x;
+        (((high >> bit) & 1) << 1) | ((low >> bit) & 1)
+    }
+
+    fn render_sprites(&mut self, bg_colors: &[u8; SCREEN_WIDTH]) {
+        let height: i16 = if self.lcdc & lcdc::OBJ_SIZE != 0 { 16 } else { 8 };
+        let ly = self.ly as i16;
+
+        let mut sprites: Vec<Sprite> = self.oam.data.chunks_exact(4)
+            .map(|entry| Sprite {
+                y: entry[0] as i16 - 16,
+                x: entry[1] as i16 - 8,
+                tile: entry[2],
+                attributes: entry[3],
+            })
+            .filter(|sprite| ly >= sprite.y && ly < sprite.y + height)
+            .take(MAX_SPRITES_PER_LINE)
+            .collect();
+        // Lower X coordinates win, ties being resolved by OAM order as the sort is stable.
+        sprites.sort_by_key(|sprite| sprite.x);
+
+        let row = self.ly as usize * SCREEN_WIDTH;
+        let mut drawn = [false; SCREEN_WIDTH];
+        for sprite in sprites {
+            let mut line = (ly - sprite.y) as u8;
+            if sprite.attributes & attributes::Y_FLIP != 0 {
+                line = height as u8 - 1 - line;
+            }
+            let tile = if height == 16 { sprite.tile & 0xFE } else { sprite.tile };
+            let tile_address = 0x8000 + tile as u16 * 16;
+            let palette = if sprite.attributes & attributes::PALETTE != 0 { self.obp1 } else { self.obp0 };
+
+            for column in 0..8 {
+                let x = sprite.x + column;
+                if !(0..SCREEN_WIDTH as i16).contains(&x) || drawn[x as usize] {
+                    continue;
+                }
+                let tile_x = if sprite.attributes & attributes::X_FLIP != 0 { 7 - column } else { column };
+                let color = self.tile_color(tile_address, tile_x as u8, line);
+                if color == 0 {
+                    continue;
+                }
+                // The highest priority opaque sprite pixel hides the others, even when it is
+                // itself hidden behind the background.
+                drawn[x as usize] = true;
+                if sprite.attributes & attributes::BG_PRIORITY != 0 && bg_colors[x as usize] != 0 {
+                    continue;
+                }
+                self.framebuffer[row + x as usize] = Self::shade(palette, color);
+            }
+        }
+    }
+
+    fn shade(palette: u8, color: u8) -> u8 {
+        (palette >> (color * 2)) & 0b11
+    }
+}
+
+impl Clocked for PPU {
+    /// Advances the PPU by `cycles` dots, a dot lasting one T-cycle.
+    fn tick(&mut self, cycles: u8, interrupts: &mut Interrupts) {
+        if !self.lcd_enabled() {
+            return;
+        }
+        for _ in 0..cycles {
+            self.tick_dot(interrupts);
+        }
+    }
+}
+
+impl Snapshot for PPU {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_memory(&self.vram);
+        state.write_memory(&self.oam);
+        for register in [self.lcdc, self.stat, self.scy, self.scx, self.ly, self.lyc, self.bgp, self.obp0, self.obp1, self.wy, self.wx] {
+            state.write_u8(register);
+        }
+        state.write_u8(self.mode as u8);
+        state.write_u16(self.dots);
+        state.write_u8(self.window_line);
+        state.write_bool(self.stat_line);
+        state.write_bytes(&self.framebuffer);
+        state.write_bool(self.frame_ready);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        state.read_memory(&mut self.vram)?;
+        state.read_memory(&mut self.oam)?;
+        for register in [&mut self.lcdc, &mut self.stat, &mut self.scy, &mut self.scx, &mut self.ly, &mut self.lyc,
+                         &mut self.bgp, &mut self.obp0, &mut self.obp1, &mut self.wy, &mut self.wx] {
+            *register = state.read_u8()?;
+        }
+        self.mode = match state.read_u8()? {
+            0 => Mode::HBlank,
+            1 => Mode::VBlank,
+            2 => Mode::OamScan,
+            3 => Mode::Drawing,
+            value => return Err(format!("Invalid PPU mode in save state: {}", value)),
+        };
+        self.dots = state.read_u16()?;
+        self.window_line = state.read_u8()?;
+        self.stat_line = state.read_bool()?;
+        state.read_bytes(&mut self.framebuffer)?;
+        self.frame_ready = state.read_bool()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick_dots(ppu: &mut PPU, dots: u32, interrupts: &mut Interrupts) {
+        for _ in 0..dots {
+            ppu.tick(1, interrupts);
+        }
+    }
+
+    fn requested(interrupts: &Interrupts, interrupt: Interrupt) -> bool {
+        interrupts.read_flag() & interrupt.mask() != 0
+    }
+
+    fn mode(ppu: &PPU) -> u8 {
+        ppu.read_byte(PPU::STAT_ADDRESS) & 0b11
+    }
+
+    #[test]
+    fn starts_in_the_post_boot_state() {
+        let ppu = PPU::new();
+        assert_eq!(ppu.read_byte(PPU::LCDC_ADDRESS), 0x91);
+        assert_eq!(ppu.read_byte(PPU::BGP_ADDRESS), 0xFC);
+        assert_eq!(mode(&ppu), Mode::OamScan as u8);
+    }
+
+    #[test]
+    fn walks_the_modes_of_a_scanline() {
+        let mut interrupts = Interrupts::new();
+        let mut ppu = PPU::new();
+        tick_dots(&mut ppu, OAM_SCAN_DOTS as u32, &mut interrupts);
+        assert_eq!(mode(&ppu), Mode::Drawing as u8);
+        tick_dots(&mut ppu, DRAWING_DOTS as u32, &mut interrupts);
+        assert_eq!(mode(&ppu), Mode::HBlank as u8);
+        tick_dots(&mut ppu, (DOTS_PER_LINE - OAM_SCAN_DOTS - DRAWING_DOTS) as u32, &mut interrupts);
+        assert_eq!(mode(&ppu), Mode::OamScan as u8);
+        assert_eq!(ppu.read_byte(PPU::LY_ADDRESS), 1);
+    }
+
+    #[test]
+    fn enters_vblank_after_144_lines_and_wraps_after_154() {
+        let mut interrupts = Interrupts::new();
+        let mut ppu = PPU::new();
+        tick_dots(&mut ppu, SCREEN_HEIGHT as u32 * DOTS_PER_LINE as u32 - 1, &mut interrupts);
+        assert!(!requested(&interrupts, Interrupt::VBlank));
+        tick_dots(&mut ppu, 1, &mut interrupts);
+        assert_eq!(ppu.read_byte(PPU::LY_ADDRESS), 144);
+        assert_eq!(mode(&ppu), Mode::VBlank as u8);
+        assert!(requested(&interrupts, Interrupt::VBlank));
+        assert!(ppu.take_frame());
+
+        tick_dots(&mut ppu, (LINES_PER_FRAME as u32 - SCREEN_HEIGHT as u32) * DOTS_PER_LINE as u32, &mut interrupts);
+        assert_eq!(ppu.read_byte(PPU::LY_ADDRESS), 0);
+        assert_eq!(mode(&ppu), Mode::OamScan as u8);
+    }
+
+    #[test]
+    fn requests_stat_interrupt_on_hblank_when_enabled() {
+        let mut interrupts = Interrupts::new();
+        let mut ppu = PPU::new();
+        ppu.write_byte(PPU::STAT_ADDRESS, stat::HBLANK_INTERRUPT, &mut interrupts);
+        tick_dots(&mut ppu, (OAM_SCAN_DOTS + DRAWING_DOTS) as u32 - 1, &mut interrupts);
+        assert!(!requested(&interrupts, Interrupt::LcdStat));
+        tick_dots(&mut ppu, 1, &mut interrupts);
+        assert!(requested(&interrupts, Interrupt::LcdStat));
+    }
+
+    #[test]
+    fn requests_stat_interrupt_when_ly_matches_lyc() {
+        let mut interrupts = Interrupts::new();
+        let mut ppu = PPU::new();
+        ppu.write_byte(PPU::LYC_ADDRESS, 2, &mut interrupts);
+        ppu.write_byte(PPU::STAT_ADDRESS, stat::COINCIDENCE_INTERRUPT, &mut interrupts);
+        assert_eq!(ppu.read_byte(PPU::STAT_ADDRESS) & stat::COINCIDENCE, 0);
+
+        tick_dots(&mut ppu, 2 * DOTS_PER_LINE as u32 - 1, &mut interrupts);
+        assert!(!requested(&interrupts, Interrupt::LcdStat));
+        tick_dots(&mut ppu, 1, &mut interrupts);
+        assert!(requested(&interrupts, Interrupt::LcdStat));
+        assert_ne!(ppu.read_byte(PPU::STAT_ADDRESS) & stat::COINCIDENCE, 0);
+
+        // The interrupt is requested on the rising edge of the STAT line only.
+        interrupts.acknowledge(Interrupt::LcdStat);
+        tick_dots(&mut ppu, DOTS_PER_LINE as u32 - 1, &mut interrupts);
+        assert!(!requested(&interrupts, Interrupt::LcdStat));
+    }
+
+    #[test]
+    fn disabled_background_is_white() {
+        let mut interrupts = Interrupts::new();
+        let mut ppu = PPU::new();
+        ppu.write_byte(PPU::BGP_ADDRESS, 0xFF, &mut interrupts);
+        tick_dots(&mut ppu, DOTS_PER_LINE as u32, &mut interrupts);
+        assert!(ppu.framebuffer()[..SCREEN_WIDTH].iter().all(|&shade| shade == 3));
+
+        ppu.write_byte(PPU::LCDC_ADDRESS, lcdc::LCD_ENABLE, &mut interrupts);
+        tick_dots(&mut ppu, DOTS_PER_LINE as u32, &mut interrupts);
+        assert!(ppu.framebuffer()[SCREEN_WIDTH..2 * SCREEN_WIDTH].iter().all(|&shade| shade == 0));
+    }
+}
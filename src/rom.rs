@@ -36,7 +36,7 @@ impl Rom {
         let memory = Memory { data: fs::read(path)? };
         match Rom::extract_header(&memory) {
             Some(header) => Ok(Rom { memory: memory.clone(), header }),
-            None => Err(*Box::new(std::io::Error::new(std::io::ErrorKind::Other, "Unable to parse ROM header")))
+            None => Err(std::io::Error::other("Unable to parse ROM header"))
         }
     }
 
@@ -105,6 +105,10 @@ impl Rom {
         })
     }
 
+    pub(crate) fn header(&self) -> &CartridgeHeader {
+        &self.header
+    }
+
     pub(crate) fn validate(&self) -> bool {
         self.memory.data[0x104..0x134] == Self::NINTENDO_LOGO
     }
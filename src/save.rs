@@ -0,0 +1,164 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use log::{debug, error, info};
+
+use crate::mbc::Mapper;
+
+/// Save file holding the battery-backed memory of a cartridge, `<rom>.sav` next to the ROM.
+pub(crate) struct SaveFile {
+    path: PathBuf,
+    /// Data last read from or written to the file, to skip writing it again when unchanged.
+    saved: Vec<u8>,
+}
+
+impl SaveFile {
+    pub(crate) fn for_rom(rom_path: &Path) -> Self {
+        SaveFile {
+            path: rom_path.with_extension("sav"),
+            saved: Vec::new(),
+        }
+    }
+
+    /// Restores the battery-backed memory of the cartridge, if it has one and a save exists.
+    pub(crate) fn load(&mut self, cartridge: &mut dyn Mapper) -> Result<(), String> {
+        if !cartridge.has_battery() {
+            return Ok(());
+        }
+        match fs::read(&self.path) {
+            Ok(data) => {
+                cartridge.load_data(&data)
+                    .map_err(|err| format!("Failed to load save {}: {}", self.path.display(), err))?;
+                info!("Save loaded from {}", self.path.display());
+                self.saved = data;
+                Ok(())
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(format!("Failed to read save {}: {}", self.path.display(), err)),
+        }
+    }
+
+    /// Writes the battery-backed memory of the cartridge if it changed since the last flush.
+    pub(crate) fn flush(&mut self, cartridge: &mut dyn Mapper) {
+        if !cartridge.has_battery() {
+            return;
+        }
+        let data = cartridge.save_data();
+        if data == self.saved {
+            return;
+        }
+        match write_atomically(&self.path, &data) {
+            Ok(()) => {
+                debug!("Save written to {}", self.path.display());
+                self.saved = data;
+            }
+            Err(err) => error!("Failed to write save {}: {}", self.path.display(), err),
+        }
+    }
+}
+
+/// Writes `data` to a temporary file next to `path` then renames it to `path`, so that `path`
+/// holds either its previous contents or `data` even if the process is killed mid-write.
+pub(crate) fn write_atomically(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let temporary = PathBuf::from(temporary);
+    let mut file = File::create(&temporary)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    fs::rename(&temporary, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mbc::{self, RtcClock};
+    use crate::rom::Rom;
+
+    /// Empty directory for the files of `test`, in the temporary directory of the system.
+    fn temp_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("crabboy-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Writes an MBC1+RAM+BATTERY ROM with 8 KiB of RAM to `dir`, returning its path.
+    fn write_rom(dir: &Path) -> PathBuf {
+        let mut data = vec![0; 0x8000];
+        data[0x0147] = 0x03;
+        data[0x0149] = 0x02;
+        let path = dir.join("game.gb");
+        fs::write(&path, data).unwrap();
+        path
+    }
+
+    /// Cartridge of the ROM at `rom_path`, with its RAM enabled.
+    fn cartridge(rom_path: &Path) -> Box<dyn Mapper> {
+        let rom = Rom::from_path(rom_path.to_str().unwrap()).unwrap();
+        let mut cartridge = mbc::from_rom(rom, RtcClock::Emulated).unwrap();
+        cartridge.write_rom(0x0000, 0x0A);
+        cartridge
+    }
+
+    #[test]
+    fn loads_a_save_sized_from_the_ram_size_of_the_header() {
+        let dir = temp_dir("save-load");
+        let rom_path = write_rom(&dir);
+        let data: Vec<u8> = (0..0x2000).map(|offset| offset as u8).collect();
+        fs::write(dir.join("game.sav"), &data).unwrap();
+
+        let mut cartridge = cartridge(&rom_path);
+        SaveFile::for_rom(&rom_path).load(cartridge.as_mut()).unwrap();
+        assert_eq!(cartridge.read_ram(0xA000), 0x00);
+        assert_eq!(cartridge.read_ram(0xA123), 0x23);
+        assert_eq!(cartridge.read_ram(0xBFFF), 0xFF);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn flushed_saves_are_loaded_back() {
+        let dir = temp_dir("save-flush");
+        let rom_path = write_rom(&dir);
+        let mut cartridge = cartridge(&rom_path);
+        let mut save = SaveFile::for_rom(&rom_path);
+        save.load(cartridge.as_mut()).unwrap();
+        cartridge.write_ram(0xA010, 0x42);
+        save.flush(cartridge.as_mut());
+
+        let data = fs::read(dir.join("game.sav")).unwrap();
+        assert_eq!(data.len(), 0x2000);
+        assert_eq!(data[0x10], 0x42);
+        assert!(!dir.join("game.sav.tmp").exists());
+
+        let mut reloaded = self::cartridge(&rom_path);
+        SaveFile::for_rom(&rom_path).load(reloaded.as_mut()).unwrap();
+        assert_eq!(reloaded.read_ram(0xA010), 0x42);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn saves_of_the_wrong_size_are_refused() {
+        let dir = temp_dir("save-size");
+        let rom_path = write_rom(&dir);
+        fs::write(dir.join("game.sav"), [0x42; 100]).unwrap();
+
+        let mut cartridge = cartridge(&rom_path);
+        let err = SaveFile::for_rom(&rom_path).load(cartridge.as_mut()).unwrap_err();
+        assert!(err.contains("Failed to load save"), "{}", err);
+        assert_eq!(cartridge.read_ram(0xA000), 0x00);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn atomic_writes_replace_the_file() {
+        let dir = temp_dir("save-atomic");
+        let path = dir.join("file");
+        fs::write(&path, b"previous contents").unwrap();
+        write_atomically(&path, b"new").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,275 @@
+//! Save states, snapshots of the whole machine that can be restored later.
+//!
+//! A save state starts with a header made of the `CBSS` magic, the format version and the CRC-32
+//! of the ROM it was made with, followed by the state of each component in a fixed order. Values
+//! are stored little-endian, and memories prefixed with their size.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cpu::CPU;
+use crate::mmu::MMU;
+use crate::save::write_atomically;
+use crate::types::Memory;
+
+const MAGIC: [u8; 4] = *b"CBSS";
+
+/// Version of the format, to be incremented whenever the state of a component changes.
+const FORMAT_VERSION: u16 = 1;
+
+/// Size of the header: magic, format version and ROM checksum.
+const HEADER_SIZE: usize = MAGIC.len() + 2 + 4;
+
+/// Number of save state slots of a ROM.
+pub(crate) const SLOTS: u8 = 10;
+
+/// Component of the machine whose state is saved.
+pub(crate) trait Snapshot {
+    fn save_state(&self, state: &mut StateWriter);
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String>;
+}
+
+/// Serializer of the state of the components.
+pub(crate) struct StateWriter {
+    data: Vec<u8>,
+}
+
+impl StateWriter {
+    fn new() -> Self {
+        StateWriter { data: Vec::new() }
+    }
+
+    pub(crate) fn write_u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    pub(crate) fn write_bool(&mut self, value: bool) {
+        self.data.push(value as u8);
+    }
+
+    pub(crate) fn write_u16(&mut self, value: u16) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn write_u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn write_u64(&mut self, value: u64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn write_f32(&mut self, value: f32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Writes bytes whose number is fixed by the component.
+    pub(crate) fn write_bytes(&mut self, bytes: &[u8]) {
+        self.data.extend_from_slice(bytes);
+    }
+
+    pub(crate) fn write_memory(&mut self, memory: &Memory) {
+        self.write_u32(memory.data.len() as u32);
+        self.write_bytes(&memory.data);
+    }
+}
+
+/// Deserializer of the state of the components.
+pub(crate) struct StateReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> StateReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        StateReader { data }
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let (bytes, rest) = self.data.split_first_chunk::<N>().ok_or("Truncated save state")?;
+        self.data = rest;
+        Ok(*bytes)
+    }
+
+    pub(crate) fn read_u8(&mut self) -> Result<u8, String> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    pub(crate) fn read_bool(&mut self) -> Result<bool, String> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(format!("Invalid boolean in save state: {}", value)),
+        }
+    }
+
+    pub(crate) fn read_u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.take()?))
+    }
+
+    pub(crate) fn read_u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    pub(crate) fn read_u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.take()?))
+    }
+
+    pub(crate) fn read_f32(&mut self) -> Result<f32, String> {
+        Ok(f32::from_le_bytes(self.take()?))
+    }
+
+    /// Fills `bytes`, whose number is fixed by the component.
+    pub(crate) fn read_bytes(&mut self, bytes: &mut [u8]) -> Result<(), String> {
+        if self.data.len() < bytes.len() {
+            return Err(String::from("Truncated save state"));
+        }
+        let (read, rest) = self.data.split_at(bytes.len());
+        bytes.copy_from_slice(read);
+        self.data = rest;
+        Ok(())
+    }
+
+    /// Restores `memory`, which must have the size it was saved with.
+    pub(crate) fn read_memory(&mut self, memory: &mut Memory) -> Result<(), String> {
+        let size = self.read_u32()? as usize;
+        if size != memory.data.len() {
+            return Err(format!("Invalid memory size in save state: {} bytes, expected {}", size, memory.data.len()));
+        }
+        self.read_bytes(&mut memory.data)
+    }
+}
+
+/// Serializes the state of the machine.
+pub(crate) fn save(cpu: &CPU, mmu: &MMU) -> Vec<u8> {
+    let mut state = StateWriter::new();
+    state.write_bytes(&MAGIC);
+    state.write_u16(FORMAT_VERSION);
+    state.write_u32(mmu.rom_checksum());
+    cpu.save_state(&mut state);
+    mmu.save_state(&mut state);
+    state.data
+}
+
+fn load_components(data: &[u8], cpu: &mut CPU, mmu: &mut MMU) -> Result<(), String> {
+    let mut state = StateReader::new(data);
+    cpu.load_state(&mut state)?;
+    mmu.load_state(&mut state)?;
+    if !state.data.is_empty() {
+        return Err(format!("Invalid save state, {} bytes left after the last component", state.data.len()));
+    }
+    Ok(())
+}
+
+/// Restores the state of the machine, refusing states made with another ROM or format version.
+///
+/// The machine is left untouched when the state cannot be restored.
+pub(crate) fn load(data: &[u8], cpu: &mut CPU, mmu: &mut MMU) -> Result<(), String> {
+    let mut header = StateReader::new(data);
+    if header.take::<4>().ok() != Some(MAGIC) {
+        return Err(String::from("Not a save state"));
+    }
+    let version = header.read_u16()?;
+    if version != FORMAT_VERSION {
+        return Err(format!("Unsupported save state format version {}, expected {}", version, FORMAT_VERSION));
+    }
+    let checksum = header.read_u32()?;
+    if checksum != mmu.rom_checksum() {
+        return Err(format!("The save state was made with another ROM (CRC-32 {:08X}, expected {:08X})",
+                           checksum, mmu.rom_checksum()));
+    }
+    let backup = save(cpu, mmu);
+    if let Err(err) = load_components(header.data, cpu, mmu) {
+        load_components(&backup[HEADER_SIZE..], cpu, mmu)
+            .expect("Failed to restore the machine from its own state");
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// Save state slots of a ROM, `<rom>.ss<slot>` next to the ROM.
+#[derive(Clone)]
+pub(crate) struct StateSlots {
+    rom_path: PathBuf,
+}
+
+impl StateSlots {
+    pub(crate) fn for_rom(rom_path: &Path) -> Self {
+        StateSlots { rom_path: rom_path.to_path_buf() }
+    }
+
+    pub(crate) fn path(&self, slot: u8) -> PathBuf {
+        self.rom_path.with_extension(format!("ss{}", slot))
+    }
+
+    pub(crate) fn save(&self, slot: u8, cpu: &CPU, mmu: &MMU) -> Result<PathBuf, String> {
+        let path = self.path(slot);
+        write_atomically(&path, &save(cpu, mmu)).map_err(|err| format!("Failed to write save state {}: {}", path.display(), err))?;
+        Ok(path)
+    }
+
+    pub(crate) fn load(&self, slot: u8, cpu: &mut CPU, mmu: &mut MMU) -> Result<PathBuf, String> {
+        let path = self.path(slot);
+        let data = fs::read(&path).map_err(|err| format!("Failed to read save state {}: {}", path.display(), err))?;
+        load(&data, cpu, mmu).map_err(|err| format!("Failed to load save state {}: {}", path.display(), err))?;
+        Ok(path)
+    }
+}
+
+/// Parses a save state slot number.
+pub(crate) fn parse_slot(slot: &str) -> Result<u8, String> {
+    match slot.parse::<u8>() {
+        Ok(slot) if slot < SLOTS => Ok(slot),
+        _ => Err(format!("Invalid save state slot {}, expected 0 to {}", slot, SLOTS - 1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Machine running a loop incrementing a counter in work RAM.
+    fn machine() -> (CPU, MMU) {
+        crate::cpu::machine(&[
+            0x21, 0x00, 0xD0, // LD HL, 0xD000
+            0x34, // INC (HL)
+            0xC3, 0x03, 0xC0, // JP 0xC003
+        ])
+    }
+
+    #[test]
+    fn loading_a_state_restores_the_machine() {
+        let (mut cpu, mut mmu) = machine();
+        for _ in 0..1000 {
+            cpu.step(&mut mmu);
+        }
+        let state = save(&cpu, &mmu);
+        for _ in 0..1000 {
+            cpu.step(&mut mmu);
+        }
+        assert_ne!(save(&cpu, &mmu), state);
+        load(&state, &mut cpu, &mut mmu).unwrap();
+        assert_eq!(save(&cpu, &mmu), state);
+    }
+
+    #[test]
+    fn states_of_another_rom_are_refused() {
+        let (mut cpu, mut mmu) = machine();
+        let mut state = save(&cpu, &mmu);
+        state[MAGIC.len() + 2] ^= 0xFF;
+        cpu.step(&mut mmu);
+        let before = save(&cpu, &mmu);
+        assert!(load(&state, &mut cpu, &mut mmu).unwrap_err().contains("another ROM"));
+        assert_eq!(save(&cpu, &mmu), before);
+    }
+
+    #[test]
+    fn truncated_states_leave_the_machine_untouched() {
+        let (mut cpu, mut mmu) = machine();
+        let state = save(&cpu, &mmu);
+        cpu.step(&mut mmu);
+        let before = save(&cpu, &mmu);
+        assert!(load(&state[..state.len() - 1], &mut cpu, &mut mmu).is_err());
+        assert_eq!(save(&cpu, &mmu), before);
+    }
+}
@@ -0,0 +1,177 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::checksum::crc32;
+use crate::ppu::{SCREEN_HEIGHT, SCREEN_WIDTH};
+
+/// Grey levels of the four DMG shades, from white to black.
+const GREY_LEVELS: [u8; 4] = [0xFF, 0xAA, 0x55, 0x00];
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// Largest payload of an uncompressed deflate block.
+const MAX_STORED_BLOCK: usize = 0xFFFF;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum ImageFormat {
+    Png,
+    Ppm,
+}
+
+impl ImageFormat {
+    pub(crate) fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "png" => Some(ImageFormat::Png),
+            "ppm" => Some(ImageFormat::Ppm),
+            _ => None,
+        }
+    }
+}
+
+/// Returns `path` with the frame number appended to its file stem, e.g. `shot_0042.png`.
+pub(crate) fn numbered_path(path: &Path, frame: u32) -> PathBuf {
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy()).unwrap_or_default();
+    let file_name = match path.extension() {
+        Some(extension) => format!("{}_{:04}.{}", stem, frame, extension.to_string_lossy()),
+        None => format!("{}_{:04}", stem, frame),
+    };
+    path.with_file_name(file_name)
+}
+
+/// Writes a framebuffer of shades to `path` in the given format.
+pub(crate) fn save(path: &Path, format: ImageFormat, framebuffer: &[u8]) -> io::Result<()> {
+    let grey: Vec<u8> = framebuffer.iter().map(|&shade| GREY_LEVELS[(shade & 0b11) as usize]).collect();
+    let data = match format {
+        ImageFormat::Png => encode_png(&grey, SCREEN_WIDTH, SCREEN_HEIGHT),
+        ImageFormat::Ppm => encode_ppm(&grey, SCREEN_WIDTH, SCREEN_HEIGHT),
+    };
+    fs::write(path, data)
+}
+
+/// Encodes a binary (P6) PPM image of `width` by `height` grey levels.
+fn encode_ppm(grey: &[u8], width: usize, height: usize) -> Vec<u8> {
+    let mut data = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    for &level in grey {
+        data.extend_from_slice(&[level, level, level]);
+    }
+    data
+}
+
+/// Encodes an 8-bit greyscale PNG image of `width` by `height` grey levels, stored without
+/// compression.
+fn encode_png(grey: &[u8], width: usize, height: usize) -> Vec<u8> {
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    // Bit depth 8, greyscale, deflate, adaptive filtering, no interlace.
+    header.extend_from_slice(&[8, 0, 0, 0, 0]);
+
+    // Each scanline starts with its filter type, 0 meaning none.
+    let mut scanlines = Vec::with_capacity((width + 1) * height);
+    for row in grey.chunks_exact(width) {
+        scanlines.push(0);
+        scanlines.extend_from_slice(row);
+    }
+
+    let mut data = PNG_SIGNATURE.to_vec();
+    write_png_chunk(&mut data, b"IHDR", &header);
+    write_png_chunk(&mut data, b"IDAT", &zlib_stored(&scanlines));
+    write_png_chunk(&mut data, b"IEND", &[]);
+    data
+}
+
+fn write_png_chunk(data: &mut Vec<u8>, kind: &[u8; 4], payload: &[u8]) {
+    data.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    let start = data.len();
+    data.extend_from_slice(kind);
+    data.extend_from_slice(payload);
+    let crc = crc32(&data[start..]);
+    data.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Wraps `payload` in a zlib stream made of uncompressed deflate blocks.
+fn zlib_stored(payload: &[u8]) -> Vec<u8> {
+    let mut data = vec![0x78, 0x01];
+    let mut blocks = payload.chunks(MAX_STORED_BLOCK).peekable();
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let length = block.len() as u16;
+        data.push(last as u8);
+        data.extend_from_slice(&length.to_le_bytes());
+        data.extend_from_slice(&(!length).to_le_bytes());
+        data.extend_from_slice(block);
+    }
+    data.extend_from_slice(&adler32(payload).to_be_bytes());
+    data
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2x2 image of the four shades, from white to black.
+    const GREY: [u8; 4] = [0xFF, 0xAA, 0x55, 0x00];
+
+    /// Splits a PNG image after its signature into its (type, payload) chunks, checking their CRCs.
+    fn png_chunks(data: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let mut chunks = Vec::new();
+        let mut rest = &data[PNG_SIGNATURE.len()..];
+        while !rest.is_empty() {
+            let length = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+            let (typed, crc) = rest[4..].split_at(4 + length);
+            assert_eq!(crc32(typed).to_be_bytes(), crc[..4]);
+            chunks.push((String::from_utf8(typed[..4].to_vec()).unwrap(), typed[4..].to_vec()));
+            rest = &crc[4..];
+        }
+        chunks
+    }
+
+    #[test]
+    fn encodes_ppm() {
+        let mut expected = b"P6\n2 2\n255\n".to_vec();
+        expected.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xAA, 0xAA, 0xAA, 0x55, 0x55, 0x55, 0x00, 0x00, 0x00]);
+        assert_eq!(encode_ppm(&GREY, 2, 2), expected);
+    }
+
+    #[test]
+    fn encodes_png_chunks() {
+        let data = encode_png(&GREY, 2, 2);
+        assert_eq!(data[..8], PNG_SIGNATURE);
+        // The CRC of an IEND chunk is always the same.
+        assert_eq!(data[data.len() - 4..], [0xAE, 0x42, 0x60, 0x82]);
+
+        let chunks = png_chunks(&data);
+        let types: Vec<&str> = chunks.iter().map(|(kind, _)| kind.as_str()).collect();
+        assert_eq!(types, ["IHDR", "IDAT", "IEND"]);
+        assert_eq!(chunks[0].1, [0, 0, 0, 2, 0, 0, 0, 2, 8, 0, 0, 0, 0]);
+
+        let scanlines = [0x00, 0xFF, 0xAA, 0x00, 0x55, 0x00];
+        let mut idat = vec![0x78, 0x01, 0x01, 0x06, 0x00, 0xF9, 0xFF];
+        idat.extend_from_slice(&scanlines);
+        idat.extend_from_slice(&adler32(&scanlines).to_be_bytes());
+        assert_eq!(chunks[1].1, idat);
+        assert!(chunks[2].1.is_empty());
+    }
+
+    #[test]
+    fn computes_adler32() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn numbers_frames_in_the_file_stem() {
+        assert_eq!(numbered_path(Path::new("out/shot.png"), 42), Path::new("out/shot_0042.png"));
+        assert_eq!(numbered_path(Path::new("shot"), 7), Path::new("shot_0007"));
+    }
+}
@@ -0,0 +1,202 @@
+//! Link cable between two instances of the emulator over a local socket.
+//!
+//! The instances run in lockstep: every `SYNC_CYCLES` T-cycles of emulated time, each of them
+//! sends the state of its serial port and waits for the state of its peer at the same point of
+//! emulated time. Transfers are only resolved from these states, so that they do not depend on
+//! how fast either instance runs:
+//!
+//! * a transfer clocked internally completes at the first sync point after its 8 bits were
+//!   shifted out, receiving the byte of the peer if it waits for an external clock and 0xFF
+//!   otherwise;
+//! * a transfer waiting for an external clock completes at the sync point where the peer
+//!   completes a transfer clocked internally, receiving its byte.
+//!
+//! Each sync point is a 2-byte message, the state of the port followed by SB, after a handshake
+//! checking both instances speak the same protocol.
+
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+
+use log::{error, info};
+
+use crate::serial::{Port, SerialEndpoint};
+
+/// T-cycles of emulated time between two sync points, the duration of 2 bits of a transfer.
+const SYNC_CYCLES: u32 = 1024;
+
+const HANDSHAKE: [u8; 4] = *b"CBL1";
+
+const PORT_IDLE: u8 = 0;
+const PORT_INTERNAL: u8 = 1;
+const PORT_SHIFTED: u8 = 2;
+const PORT_EXTERNAL: u8 = 3;
+
+/// Address of a link cable socket: `unix:<path>` for a Unix-domain socket, `<host>:<port>` for
+/// TCP.
+#[derive(Debug, Clone)]
+pub(crate) enum LinkAddress {
+    Tcp(String),
+    Unix(PathBuf),
+}
+
+impl LinkAddress {
+    pub(crate) fn parse(address: &str) -> Self {
+        match address.strip_prefix("unix:") {
+            Some(path) => LinkAddress::Unix(PathBuf::from(path)),
+            None => LinkAddress::Tcp(address.to_string()),
+        }
+    }
+}
+
+trait Stream: Read + Write {}
+
+impl<T: Read + Write> Stream for T {}
+
+/// Link cable plugged into another instance.
+pub(crate) struct Link {
+    /// Connection to the peer, dropped on error to leave the cable unplugged.
+    stream: Option<Box<dyn Stream>>,
+    /// T-cycles elapsed since the last sync point.
+    cycles: u32,
+}
+
+impl Link {
+    /// Waits for the other instance to connect to `address`.
+    pub(crate) fn listen(address: &LinkAddress) -> io::Result<Self> {
+        info!("Waiting for the link cable peer on {:?}", address);
+        let stream: Box<dyn Stream> = match address {
+            LinkAddress::Tcp(address) => {
+                let (stream, _) = TcpListener::bind(address)?.accept()?;
+                stream.set_nodelay(true)?;
+                Box::new(stream)
+            }
+            #[cfg(unix)]
+            LinkAddress::Unix(path) => {
+                let listener = UnixListener::bind(path)?;
+                let accepted = listener.accept();
+                std::fs::remove_file(path)?;
+                Box::new(accepted?.0)
+            }
+            #[cfg(not(unix))]
+            LinkAddress::Unix(_) => return Err(io::Error::other("Unix-domain sockets are not supported")),
+        };
+        Self::handshake(stream)
+    }
+
+    /// Connects to the other instance listening on `address`.
+    pub(crate) fn connect(address: &LinkAddress) -> io::Result<Self> {
+        let stream: Box<dyn Stream> = match address {
+            LinkAddress::Tcp(address) => {
+                let stream = TcpStream::connect(address)?;
+                stream.set_nodelay(true)?;
+                Box::new(stream)
+            }
+            #[cfg(unix)]
+            LinkAddress::Unix(path) => Box::new(UnixStream::connect(path)?),
+            #[cfg(not(unix))]
+            LinkAddress::Unix(_) => return Err(io::Error::other("Unix-domain sockets are not supported")),
+        };
+        Self::handshake(stream)
+    }
+
+    fn handshake(mut stream: Box<dyn Stream>) -> io::Result<Self> {
+        stream.write_all(&HANDSHAKE)?;
+        let mut handshake = [0; HANDSHAKE.len()];
+        stream.read_exact(&mut handshake)?;
+        if handshake != HANDSHAKE {
+            return Err(io::Error::other("The peer does not speak the link cable protocol"));
+        }
+        info!("Link cable connected");
+        Ok(Link { stream: Some(stream), cycles: 0 })
+    }
+
+    /// Sends the state of the port and returns the state of the peer at the same sync point.
+    fn sync(stream: &mut dyn Stream, port: Port) -> io::Result<Port> {
+        let message = match port {
+            Port::Idle => [PORT_IDLE, 0xFF],
+            Port::Internal { data, shifted: false } => [PORT_INTERNAL, data],
+            Port::Internal { data, shifted: true } => [PORT_SHIFTED, data],
+            Port::External { data } => [PORT_EXTERNAL, data],
+        };
+        stream.write_all(&message)?;
+        let mut peer = [0; 2];
+        stream.read_exact(&mut peer)?;
+        match peer {
+            [PORT_IDLE, _] => Ok(Port::Idle),
+            [PORT_INTERNAL, data] => Ok(Port::Internal { data, shifted: false }),
+            [PORT_SHIFTED, data] => Ok(Port::Internal { data, shifted: true }),
+            [PORT_EXTERNAL, data] => Ok(Port::External { data }),
+            [state, _] => Err(io::Error::other(format!("Invalid port state {} from the peer", state))),
+        }
+    }
+}
+
+impl SerialEndpoint for Link {
+    fn tick(&mut self, cycles: u8, port: Port) -> Option<u8> {
+        self.cycles += cycles as u32;
+        let Some(stream) = &mut self.stream else {
+            return port.shifted().map(|_| 0xFF);
+        };
+        if self.cycles < SYNC_CYCLES {
+            return None;
+        }
+        self.cycles -= SYNC_CYCLES;
+        let peer = match Self::sync(stream.as_mut(), port) {
+            Ok(peer) => peer,
+            Err(err) => {
+                error!("Link cable disconnected: {}", err);
+                self.stream = None;
+                return port.shifted().map(|_| 0xFF);
+            }
+        };
+        match (port, peer) {
+            (Port::Internal { shifted: true, .. }, Port::External { data }) => Some(data),
+            (Port::Internal { shifted: true, .. }, _) => Some(0xFF),
+            (Port::External { .. }, Port::Internal { data, shifted: true }) => Some(data),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::clock::Clocked;
+    use crate::interrupts::Interrupts;
+    use crate::serial::Serial;
+
+    /// Transfers `data` with the given SC value through a link cable over `stream`, returning
+    /// the byte received and the T-cycles until the transfer completed.
+    fn transfer(stream: UnixStream, data: u8, control: u8) -> (u8, u32) {
+        let link = Link::handshake(Box::new(stream)).unwrap();
+        let mut serial = Serial::new(Box::new(link));
+        let mut interrupts = Interrupts::new();
+        serial.write_byte(Serial::SB_ADDRESS, data);
+        serial.write_byte(Serial::SC_ADDRESS, control);
+        let mut cycles = 0;
+        while serial.read_byte(Serial::SC_ADDRESS) & 0x80 != 0 && cycles < 4 * 4096 {
+            serial.tick(4, &mut interrupts);
+            cycles += 4;
+        }
+        (serial.read_byte(Serial::SB_ADDRESS), cycles)
+    }
+
+    #[test]
+    fn both_instances_receive_the_byte_of_their_peer_at_the_same_sync_point() {
+        let (master, slave) = UnixStream::pair().unwrap();
+        let slave = thread::spawn(move || transfer(slave, 0x34, 0x80));
+        let (master_received, master_cycles) = transfer(master, 0x12, 0x81);
+        let (slave_received, slave_cycles) = slave.join().unwrap();
+
+        assert_eq!(master_received, 0x34);
+        assert_eq!(slave_received, 0x12);
+        assert_eq!(master_cycles, 4096);
+        assert_eq!(master_cycles % SYNC_CYCLES, 0);
+        assert_eq!(slave_cycles, master_cycles);
+    }
+}
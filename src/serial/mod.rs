@@ -0,0 +1,184 @@
+//! Serial port and the devices that can be plugged into it.
+
+use crate::clock::Clocked;
+use crate::interrupts::{Interrupt, Interrupts};
+use crate::save_state::{Snapshot, StateReader, StateWriter};
+
+pub(crate) use link::{Link, LinkAddress};
+pub(crate) use output::OutputEndpoint;
+
+mod link;
+mod output;
+
+/// State of the serial port, as seen from the link cable.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Port {
+    Idle,
+    /// Transfer clocked by the Game Boy, `shifted` once its 8 bits were shifted out.
+    Internal { data: u8, shifted: bool },
+    /// Transfer waiting for the device at the other end to clock it.
+    External { data: u8 },
+}
+
+impl Port {
+    /// Byte of a transfer clocked by the Game Boy whose bits were all shifted out.
+    pub(crate) fn shifted(&self) -> Option<u8> {
+        match *self {
+            Port::Internal { data, shifted: true } => Some(data),
+            _ => None,
+        }
+    }
+}
+
+/// Device at the other end of the link cable.
+pub(crate) trait SerialEndpoint {
+    /// Advances the device by `cycles` T-cycles alongside the Game Boy, whose serial port is in
+    /// the `port` state. Returns the byte received by the Game Boy when the transfer completes.
+    fn tick(&mut self, cycles: u8, port: Port) -> Option<u8>;
+}
+
+/// Unplugged link cable, whose input line is pulled high.
+pub(crate) struct Disconnected;
+
+impl SerialEndpoint for Disconnected {
+    fn tick(&mut self, _cycles: u8, port: Port) -> Option<u8> {
+        port.shifted().map(|_| 0xFF)
+    }
+}
+
+/// Serial port registers SB (0xFF01) and SC (0xFF02).
+///
+/// A transfer starts when bit 7 of SC is set. With the internal clock, bit 0 of SC, the 8 bits of
+/// SB are shifted out at 8192 Hz, taking 4096 T-cycles. With the external clock, the device at
+/// the other end clocks the transfer. Either way, the device decides when the transfer completes:
+/// SB then holds the byte received, bit 7 of SC is cleared and the serial interrupt is requested.
+pub(crate) struct Serial {
+    data: u8,
+    control: u8,
+    /// T-cycles elapsed since the start of the current transfer clocked internally.
+    transfer_cycles: u16,
+    endpoint: Box<dyn SerialEndpoint>,
+}
+
+impl Serial {
+    pub(crate) const SB_ADDRESS: u16 = 0xFF01;
+    pub(crate) const SC_ADDRESS: u16 = 0xFF02;
+
+    const SC_TRANSFER_START: u8 = 0b1000_0000;
+    const SC_INTERNAL_CLOCK: u8 = 0b0000_0001;
+
+    /// T-cycles needed to shift a byte with the internal clock.
+    const TRANSFER_CYCLES: u16 = 4096;
+
+    pub(crate) fn new(endpoint: Box<dyn SerialEndpoint>) -> Self {
+        Serial {
+            data: 0,
+            control: 0,
+            transfer_cycles: 0,
+            endpoint,
+        }
+    }
+
+    pub(crate) fn read_byte(&self, addr: u16) -> u8 {
+        match addr {
+            Self::SB_ADDRESS => self.data,
+            Self::SC_ADDRESS => self.control | 0b0111_1110,
+            _ => 0xFF,
+        }
+    }
+
+    pub(crate) fn write_byte(&mut self, addr: u16, value: u8) {
+        match addr {
+            Self::SB_ADDRESS => self.data = value,
+            Self::SC_ADDRESS => {
+                self.control = value & (Self::SC_TRANSFER_START | Self::SC_INTERNAL_CLOCK);
+                self.transfer_cycles = 0;
+            }
+            _ => (),
+        }
+    }
+
+    fn complete_transfer(&mut self, received: u8, interrupts: &mut Interrupts) {
+        self.data = received;
+        self.control &= !Self::SC_TRANSFER_START;
+        interrupts.request(Interrupt::Serial);
+    }
+}
+
+impl Clocked for Serial {
+    fn tick(&mut self, cycles: u8, interrupts: &mut Interrupts) {
+        let port = if self.control & Self::SC_TRANSFER_START == 0 {
+            Port::Idle
+        } else if self.control & Self::SC_INTERNAL_CLOCK == 0 {
+            Port::External { data: self.data }
+        } else {
+            self.transfer_cycles = (self.transfer_cycles + cycles as u16).min(Self::TRANSFER_CYCLES);
+            Port::Internal { data: self.data, shifted: self.transfer_cycles == Self::TRANSFER_CYCLES }
+        };
+        if let Some(received) = self.endpoint.tick(cycles, port) {
+            self.complete_transfer(received, interrupts);
+        }
+    }
+}
+
+/// The endpoint is not part of the state: it stays plugged in when a state is loaded.
+impl Snapshot for Serial {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u8(self.data);
+        state.write_u8(self.control);
+        state.write_u16(self.transfer_cycles);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.data = state.read_u8()?;
+        self.control = state.read_u8()?;
+        self.transfer_cycles = state.read_u16()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn serial_requested(interrupts: &Interrupts) -> bool {
+        interrupts.read_flag() & Interrupt::Serial.mask() != 0
+    }
+
+    /// Serial port with nothing plugged in, transferring 0x42 with the given SC value.
+    fn transferring(control: u8) -> Serial {
+        let mut serial = Serial::new(Box::new(Disconnected));
+        serial.write_byte(Serial::SB_ADDRESS, 0x42);
+        serial.write_byte(Serial::SC_ADDRESS, control);
+        serial
+    }
+
+    #[test]
+    fn internal_clock_transfer_completes_after_8_bits_at_8192_hz() {
+        let mut interrupts = Interrupts::new();
+        let mut serial = transferring(0x81);
+        for _ in 0..(8 * 512 / 4 - 1) {
+            serial.tick(4, &mut interrupts);
+        }
+        assert_eq!(serial.read_byte(Serial::SC_ADDRESS), 0xFF);
+        assert_eq!(serial.read_byte(Serial::SB_ADDRESS), 0x42);
+        assert!(!serial_requested(&interrupts));
+
+        serial.tick(4, &mut interrupts);
+        assert_eq!(serial.read_byte(Serial::SC_ADDRESS), 0x7F);
+        assert_eq!(serial.read_byte(Serial::SB_ADDRESS), 0xFF);
+        assert!(serial_requested(&interrupts));
+    }
+
+    #[test]
+    fn external_clock_transfer_stalls_without_a_device() {
+        let mut interrupts = Interrupts::new();
+        let mut serial = transferring(0x80);
+        for _ in 0..(16 * 512 / 4) {
+            serial.tick(4, &mut interrupts);
+        }
+        assert_eq!(serial.read_byte(Serial::SC_ADDRESS), 0xFE);
+        assert_eq!(serial.read_byte(Serial::SB_ADDRESS), 0x42);
+        assert!(!serial_requested(&interrupts));
+    }
+}
@@ -0,0 +1,30 @@
+use std::io::Write;
+
+use log::error;
+
+use crate::serial::{Port, SerialEndpoint};
+
+/// Writes the bytes sent through the serial port, to stdout or a file, as test ROMs and debug
+/// builds print through it. Nothing is plugged in as far as the Game Boy can tell.
+pub(crate) struct OutputEndpoint {
+    writer: Option<Box<dyn Write>>,
+}
+
+impl OutputEndpoint {
+    pub(crate) fn new(writer: Box<dyn Write>) -> Self {
+        OutputEndpoint { writer: Some(writer) }
+    }
+}
+
+impl SerialEndpoint for OutputEndpoint {
+    fn tick(&mut self, _cycles: u8, port: Port) -> Option<u8> {
+        let byte = port.shifted()?;
+        if let Some(writer) = &mut self.writer {
+            if let Err(err) = writer.write_all(&[byte]).and_then(|()| writer.flush()) {
+                error!("Failed to write serial output, stopping it: {}", err);
+                self.writer = None;
+            }
+        }
+        Some(0xFF)
+    }
+}
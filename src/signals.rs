@@ -0,0 +1,17 @@
+//! Ctrl-C handling, stopping long runs cleanly instead of killing the process so that the save
+//! file is flushed and the debugger gets its prompt back.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Catches Ctrl-C, which then only flags the interruption.
+pub(crate) fn install() -> Result<(), String> {
+    ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::SeqCst))
+        .map_err(|err| format!("Failed to catch Ctrl-C: {}", err))
+}
+
+/// Returns whether Ctrl-C was pressed since the last call.
+pub(crate) fn take_interrupt() -> bool {
+    INTERRUPTED.swap(false, Ordering::SeqCst)
+}
@@ -0,0 +1,114 @@
+//! Symbol files (.sym) as emitted by RGBDS, holding one `<bank>:<addr> <label>` line per label.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// Labels of a ROM, keyed by bank and address.
+///
+/// Only ROM addresses are banked: the banks of the other memory areas are ignored, as they are
+/// not switched on the DMG.
+#[derive(Clone)]
+pub(crate) struct Symbols {
+    labels: BTreeMap<(usize, u16), String>,
+    addresses: HashMap<String, (usize, u16)>,
+}
+
+/// Bank of `address` when `rom_bank` is mapped at 0x4000-0x7FFF.
+fn bank_of(address: u16, rom_bank: usize) -> usize {
+    match address {
+        0x4000..=0x7FFF => rom_bank,
+        _ => 0,
+    }
+}
+
+/// Memory area of `address`: an address is only located relative to the labels of its area.
+fn area(address: u16) -> u8 {
+    match address {
+        0x0000..=0x3FFF => 0,
+        0x4000..=0x7FFF => 1,
+        0x8000..=0x9FFF => 2,
+        0xA000..=0xBFFF => 3,
+        0xC000..=0xFDFF => 4,
+        0xFE00..=0xFEFF => 5,
+        0xFF00..=0xFF7F => 6,
+        _ => 7,
+    }
+}
+
+impl Symbols {
+    /// Loads the symbols from `path`, or from `<rom>.sym` next to the ROM if it exists.
+    pub(crate) fn find(rom_path: &Path, path: Option<&Path>) -> Result<Option<Self>, String> {
+        let default_path = rom_path.with_extension("sym");
+        let path = match path {
+            Some(path) => path,
+            None if default_path.exists() => &default_path,
+            None => return Ok(None),
+        };
+        let text = fs::read_to_string(path).map_err(|err| format!("Failed to read symbols {}: {}", path.display(), err))?;
+        Self::parse(&text).map(Some).map_err(|err| format!("Invalid symbols {}: {}", path.display(), err))
+    }
+
+    pub(crate) fn parse(text: &str) -> Result<Self, String> {
+        let mut symbols = Symbols { labels: BTreeMap::new(), addresses: HashMap::new() };
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split(';').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = || format!("line {}: expected `<bank>:<addr> <label>`", number + 1);
+            let (location, label) = line.split_once(char::is_whitespace).ok_or_else(invalid)?;
+            let (bank, address) = location.split_once(':').ok_or_else(invalid)?;
+            let bank = usize::from_str_radix(bank, 16).map_err(|_| invalid())?;
+            let address = u16::from_str_radix(address, 16).map_err(|_| invalid())?;
+            let key = (if address < 0x8000 { bank } else { 0 }, address);
+            let label = label.trim().to_string();
+            // The first label of an address names it, the others can still be looked up.
+            symbols.labels.entry(key).or_insert_with(|| label.clone());
+            symbols.addresses.insert(label, key);
+        }
+        Ok(symbols)
+    }
+
+    /// Label of `address` when `rom_bank` is mapped at 0x4000-0x7FFF.
+    pub(crate) fn label(&self, address: u16, rom_bank: usize) -> Option<&str> {
+        self.labels.get(&(bank_of(address, rom_bank), address)).map(String::as_str)
+    }
+
+    /// `address` as `<label>` or `<label>+$<offset>` from the closest label before it, when
+    /// `rom_bank` is mapped at 0x4000-0x7FFF.
+    pub(crate) fn locate(&self, address: u16, rom_bank: usize) -> Option<String> {
+        let bank = bank_of(address, rom_bank);
+        let (&(label_bank, label_address), label) = self.labels.range(..=(bank, address)).next_back()?;
+        if label_bank != bank || area(label_address) != area(address) {
+            return None;
+        }
+        match address - label_address {
+            0 => Some(label.clone()),
+            offset => Some(format!("{}+${:X}", label, offset)),
+        }
+    }
+
+    /// Bank and address of `label`.
+    pub(crate) fn address(&self, label: &str) -> Option<(usize, u16)> {
+        self.addresses.get(label).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locates_addresses_from_the_closest_label_of_their_bank() {
+        let symbols = Symbols::parse("00:0150 Main\n00:0160 Main.loop\n02:4000 Bank2\n00:C000 wBuffer\n").unwrap();
+        assert_eq!(symbols.locate(0x0150, 1).as_deref(), Some("Main"));
+        assert_eq!(symbols.locate(0x015A, 1).as_deref(), Some("Main+$A"));
+        assert_eq!(symbols.locate(0x0163, 1).as_deref(), Some("Main.loop+$3"));
+        assert_eq!(symbols.locate(0x0100, 1), None);
+        assert_eq!(symbols.locate(0x4010, 2).as_deref(), Some("Bank2+$10"));
+        assert_eq!(symbols.locate(0x4010, 1), None);
+        assert_eq!(symbols.locate(0xC004, 1).as_deref(), Some("wBuffer+$4"));
+        assert_eq!(symbols.locate(0x8000, 1), None);
+    }
+}
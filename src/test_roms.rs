@@ -0,0 +1,91 @@
+//! Blargg test ROMs, expected under `tests/roms/blargg` as described in `tests/roms/README.md`.
+//!
+//! The ROMs print their results through the serial port, ending with "Passed" or "Failed". The
+//! tests are ignored by default as the ROMs are not part of the repository: run them with
+//! `cargo test -- --ignored test_roms` once fetched, a missing ROM failing its test.
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::clock::CLOCK_SPEED;
+use crate::cpu::CPU;
+use crate::mbc::RtcClock;
+use crate::mmu::MMU;
+use crate::rom::Rom;
+use crate::serial::{Port, SerialEndpoint};
+
+/// T-cycles a ROM may run for before being considered hung, 60 emulated seconds.
+const CYCLE_BUDGET: u64 = 60 * CLOCK_SPEED as u64;
+
+/// Keeps the bytes sent through the serial port.
+struct Capture(Rc<RefCell<Vec<u8>>>);
+
+impl SerialEndpoint for Capture {
+    fn tick(&mut self, _cycles: u8, port: Port) -> Option<u8> {
+        let byte = port.shifted()?;
+        self.0.borrow_mut().push(byte);
+        Some(0xFF)
+    }
+}
+
+fn rom_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/roms/blargg").join(name)
+}
+
+/// Runs the ROM until it reports its result or exhausts the cycle budget, returning its serial
+/// output.
+fn run(path: &Path) -> String {
+    let rom = Rom::from_path(path.to_str().unwrap()).expect("Failed to load ROM");
+    let serial_output = Rc::new(RefCell::new(Vec::new()));
+    let mut mmu = MMU::new().with_rom(rom, RtcClock::Emulated).expect("Unsupported ROM")
+        .with_serial_endpoint(Box::new(Capture(Rc::clone(&serial_output))));
+    let mut cpu = CPU::new();
+    let mut output = String::new();
+    let mut captured = 0;
+    while mmu.cycles() < CYCLE_BUDGET {
+        cpu.step(&mut mmu);
+        if serial_output.borrow().len() != captured {
+            captured = serial_output.borrow().len();
+            output = String::from_utf8_lossy(&serial_output.borrow()).into_owned();
+            if output.contains("Passed") || output.contains("Failed") {
+                break;
+            }
+        }
+    }
+    output
+}
+
+fn assert_passes(name: &str) {
+    let path = rom_path(name);
+    assert!(path.exists(), "{} not found, see tests/roms/README.md", path.display());
+    let output = run(&path);
+    assert!(output.contains("Passed"), "{} did not pass, serial output:\n{}", name, output);
+}
+
+macro_rules! blargg_tests {
+    ($($test:ident => $name:literal,)*) => {
+        $(
+            #[test]
+            #[ignore = "needs tests/roms"]
+            fn $test() {
+                assert_passes($name);
+            }
+        )*
+    };
+}
+
+blargg_tests! {
+    cpu_instrs_01_special => "cpu_instrs/individual/01-special.gb",
+    cpu_instrs_02_interrupts => "cpu_instrs/individual/02-interrupts.gb",
+    cpu_instrs_03_op_sp_hl => "cpu_instrs/individual/03-op sp,hl.gb",
+    cpu_instrs_04_op_r_imm => "cpu_instrs/individual/04-op r,imm.gb",
+    cpu_instrs_05_op_rp => "cpu_instrs/individual/05-op rp.gb",
+    cpu_instrs_06_ld_r_r => "cpu_instrs/individual/06-ld r,r.gb",
+    cpu_instrs_07_jr_jp_call_ret_rst => "cpu_instrs/individual/07-jr,jp,call,ret,rst.gb",
+    cpu_instrs_08_misc_instrs => "cpu_instrs/individual/08-misc instrs.gb",
+    cpu_instrs_09_op_r_r => "cpu_instrs/individual/09-op r,r.gb",
+    cpu_instrs_10_bit_ops => "cpu_instrs/individual/10-bit ops.gb",
+    cpu_instrs_11_op_a_hl => "cpu_instrs/individual/11-op a,(hl).gb",
+    instr_timing => "instr_timing/instr_timing.gb",
+}
@@ -0,0 +1,246 @@
+use crate::clock::{Clocked, M_CYCLE};
+use crate::interrupts::{Interrupt, Interrupts};
+use crate::save_state::{Snapshot, StateReader, StateWriter};
+
+/// State of TIMA around an overflow.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum TimaState {
+    Counting,
+    /// TIMA overflowed and reads 0x00 for one M-cycle before being reloaded.
+    Overflowed,
+    /// TIMA has just been reloaded from TMA: writes to TIMA are ignored during this M-cycle.
+    Reloaded,
+}
+
+/// DIV/TIMA/TMA/TAC timer (0xFF04-0xFF07).
+///
+/// DIV is the upper byte of a 16-bit divider incremented every T-cycle. TIMA increments on the
+/// falling edge of the divider bit selected by TAC, ANDed with the TAC enable bit, which is why
+/// writes to DIV and TAC may increment TIMA.
+pub(crate) struct Timer {
+    divider: u16,
+    tima: u8,
+    tma: u8,
+    tac: u8,
+    tima_state: TimaState,
+    /// T-cycles not yet accounted for, as the timer advances one M-cycle at a time.
+    pending_cycles: u8,
+}
+
+impl Timer {
+    pub(crate) const DIV_ADDRESS: u16 = 0xFF04;
+    pub(crate) const TIMA_ADDRESS: u16 = 0xFF05;
+    pub(crate) const TMA_ADDRESS: u16 = 0xFF06;
+    pub(crate) const TAC_ADDRESS: u16 = 0xFF07;
+
+    const TAC_ENABLE: u8 = 0b0000_0100;
+    const TAC_CLOCK_SELECT: u8 = 0b0000_0011;
+
+    pub(crate) fn new() -> Self {
+        Timer {
+            divider: 0,
+            tima: 0,
+            tma: 0,
+            tac: 0,
+            tima_state: TimaState::Counting,
+            pending_cycles: 0,
+        }
+    }
+
+    pub(crate) fn read_byte(&self, addr: u16) -> u8 {
+        match addr {
+            Self::DIV_ADDRESS => (self.divider >> 8) as u8,
+            Self::TIMA_ADDRESS => self.tima,
+            Self::TMA_ADDRESS => self.tma,
+            Self::TAC_ADDRESS => self.tac | !(Self::TAC_ENABLE | Self::TAC_CLOCK_SELECT),
+            _ => 0xFF,
+        }
+    }
+
+    pub(crate) fn write_byte(&mut self, addr: u16, value: u8) {
+        match addr {
+            Self::DIV_ADDRESS => {
+                let signal = self.signal();
+                self.divider = 0;
+                self.detect_falling_edge(signal);
+            }
+            Self::TIMA_ADDRESS => match self.tima_state {
+                // Writing during the overflow cycle cancels the reload and the interrupt.
+                TimaState::Overflowed => {
+                    self.tima = value;
+                    self.tima_state = TimaState::Counting;
+                }
+                TimaState::Reloaded => (),
+                TimaState::Counting => self.tima = value,
+            },
+            Self::TMA_ADDRESS => {
+                self.tma = value;
+                if self.tima_state == TimaState::Reloaded {
+                    self.tima = value;
+                }
+            }
+            Self::TAC_ADDRESS => {
+                let signal = self.signal();
+                self.tac = value & (Self::TAC_ENABLE | Self::TAC_CLOCK_SELECT);
+                self.detect_falling_edge(signal);
+            }
+            _ => (),
+        }
+    }
+
+    fn tick_m_cycle(&mut self, interrupts: &mut Interrupts) {
+        self.tima_state = match self.tima_state {
+            TimaState::Overflowed => {
+                self.tima = self.tma;
+                interrupts.request(Interrupt::Timer);
+                TimaState::Reloaded
+            }
+            TimaState::Counting | TimaState::Reloaded => TimaState::Counting,
+        };
+        let signal = self.signal();
+        self.divider = self.divider.wrapping_add(M_CYCLE as u16);
+        self.detect_falling_edge(signal);
+    }
+
+    /// Divider bit watched by TIMA for the selected frequency.
+    fn selected_bit(&self) -> u16 {
+        match self.tac & Self::TAC_CLOCK_SELECT {
+            0b00 => 1 << 9, // 4096 Hz
+            0b01 => 1 << 3, // 262144 Hz
+            0b10 => 1 << 5, // 65536 Hz
+            _ => 1 << 7,    // 16384 Hz
+        }
+    }
+
+    fn signal(&self) -> bool {
+        self.tac & Self::TAC_ENABLE != 0 && self.divider & self.selected_bit() != 0
+    }
+
+    fn detect_falling_edge(&mut self, previous_signal: bool) {
+        if previous_signal && !self.signal() {
+            self.increment_tima();
+        }
+    }
+
+    fn increment_tima(&mut self) {
+        let (tima, overflow) = self.tima.overflowing_add(1);
+        self.tima = tima;
+        if overflow {
+            self.tima_state = TimaState::Overflowed;
+        }
+    }
+}
+
+impl Clocked for Timer {
+    fn tick(&mut self, cycles: u8, interrupts: &mut Interrupts) {
+        self.pending_cycles += cycles;
+        while self.pending_cycles >= M_CYCLE {
+            self.pending_cycles -= M_CYCLE;
+            self.tick_m_cycle(interrupts);
+        }
+    }
+}
+
+impl Snapshot for Timer {
+    fn save_state(&self, state: &mut StateWriter) {
+        state.write_u16(self.divider);
+        state.write_u8(self.tima);
+        state.write_u8(self.tma);
+        state.write_u8(self.tac);
+        state.write_u8(match self.tima_state {
+            TimaState::Counting => 0,
+            TimaState::Overflowed => 1,
+            TimaState::Reloaded => 2,
+        });
+        state.write_u8(self.pending_cycles);
+    }
+
+    fn load_state(&mut self, state: &mut StateReader) -> Result<(), String> {
+        self.divider = state.read_u16()?;
+        self.tima = state.read_u8()?;
+        self.tma = state.read_u8()?;
+        self.tac = state.read_u8()?;
+        self.tima_state = match state.read_u8()? {
+            0 => TimaState::Counting,
+            1 => TimaState::Overflowed,
+            2 => TimaState::Reloaded,
+            value => return Err(format!("Invalid TIMA state in save state: {}", value)),
+        };
+        self.pending_cycles = state.read_u8()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Timer counting at 262144 Hz, on the falling edges of bit 3 of the divider.
+    fn timer() -> Timer {
+        let mut timer = Timer::new();
+        timer.write_byte(Timer::TAC_ADDRESS, 0b101);
+        timer
+    }
+
+    fn timer_requested(interrupts: &Interrupts) -> bool {
+        interrupts.read_flag() & Interrupt::Timer.mask() != 0
+    }
+
+    #[test]
+    fn div_reset_increments_tima_on_falling_edge() {
+        let mut interrupts = Interrupts::new();
+        let mut timer = timer();
+        timer.tick(8, &mut interrupts);
+        assert_eq!(timer.read_byte(Timer::TIMA_ADDRESS), 0);
+
+        timer.write_byte(Timer::DIV_ADDRESS, 0x12);
+        assert_eq!(timer.read_byte(Timer::DIV_ADDRESS), 0);
+        assert_eq!(timer.read_byte(Timer::TIMA_ADDRESS), 1);
+
+        // The selected bit is now clear: resetting DIV again makes no edge.
+        timer.write_byte(Timer::DIV_ADDRESS, 0x12);
+        assert_eq!(timer.read_byte(Timer::TIMA_ADDRESS), 1);
+    }
+
+    #[test]
+    fn overflow_reloads_tma_and_requests_interrupt_one_m_cycle_later() {
+        let mut interrupts = Interrupts::new();
+        let mut timer = timer();
+        timer.write_byte(Timer::TMA_ADDRESS, 0xAB);
+        timer.write_byte(Timer::TIMA_ADDRESS, 0xFF);
+
+        timer.tick(16, &mut interrupts);
+        assert_eq!(timer.read_byte(Timer::TIMA_ADDRESS), 0x00);
+        assert!(!timer_requested(&interrupts));
+
+        timer.tick(4, &mut interrupts);
+        assert_eq!(timer.read_byte(Timer::TIMA_ADDRESS), 0xAB);
+        assert!(timer_requested(&interrupts));
+    }
+
+    #[test]
+    fn tima_write_during_overflow_cancels_reload_and_interrupt() {
+        let mut interrupts = Interrupts::new();
+        let mut timer = timer();
+        timer.write_byte(Timer::TMA_ADDRESS, 0xAB);
+        timer.write_byte(Timer::TIMA_ADDRESS, 0xFF);
+        timer.tick(16, &mut interrupts);
+
+        timer.write_byte(Timer::TIMA_ADDRESS, 0x42);
+        timer.tick(4, &mut interrupts);
+        assert_eq!(timer.read_byte(Timer::TIMA_ADDRESS), 0x42);
+        assert!(!timer_requested(&interrupts));
+    }
+
+    #[test]
+    fn tima_write_during_reload_is_ignored() {
+        let mut interrupts = Interrupts::new();
+        let mut timer = timer();
+        timer.write_byte(Timer::TMA_ADDRESS, 0xAB);
+        timer.write_byte(Timer::TIMA_ADDRESS, 0xFF);
+        timer.tick(20, &mut interrupts);
+
+        timer.write_byte(Timer::TIMA_ADDRESS, 0x42);
+        assert_eq!(timer.read_byte(Timer::TIMA_ADDRESS), 0xAB);
+    }
+}
@@ -0,0 +1,304 @@
+//! Comparison of the execution of a ROM against a reference trace in the Gameboy Doctor format,
+//! stopping at the first instruction whose state differs.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Lines};
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::clock::CLOCK_SPEED;
+use crate::cpu::{format_trace_state, Disassembler, Registers, Tracer, CPU};
+use crate::debugger::print_memory;
+use crate::mbc::RtcClock;
+use crate::mmu::MMU;
+use crate::rom::Rom;
+use crate::symbols::Symbols;
+
+/// Instructions disassembled from PC when the traces diverge.
+const NEXT_INSTRUCTIONS: usize = 5;
+
+/// Bytes of memory printed from PC when the traces diverge.
+const MEMORY_BYTES: u16 = 32;
+
+/// Options of a comparison.
+pub(crate) struct Options {
+    pub(crate) symbols: Option<Symbols>,
+    /// Previous instructions printed when the traces diverge.
+    pub(crate) context: usize,
+    pub(crate) stub_ly: bool,
+    /// Emulated seconds after which the comparison stops.
+    pub(crate) timeout: u32,
+}
+
+enum Status {
+    Running,
+    /// Every line of the reference matched.
+    Matched,
+    Diverged(Divergence),
+    Error(String),
+}
+
+/// First instruction whose state differs from the reference, with its context.
+struct Divergence {
+    line: u64,
+    expected: String,
+    actual: String,
+    /// Previous instructions, each with its state and disassembly.
+    history: Vec<String>,
+    /// Disassembly of the instructions from PC.
+    next: Vec<String>,
+    pc: u16,
+    /// Memory from PC, captured before the diverging instruction is executed.
+    memory: Vec<u8>,
+}
+
+/// Comparison fed by the CPU with its state before every instruction.
+struct Comparison {
+    reference: Lines<Box<dyn BufRead>>,
+    /// Lines of the reference matched so far.
+    matched: u64,
+    history: VecDeque<String>,
+    context: usize,
+    disassembler: Disassembler,
+    status: Status,
+}
+
+impl Comparison {
+    fn new(reference: Box<dyn BufRead>, context: usize, disassembler: Disassembler) -> Self {
+        Comparison {
+            reference: reference.lines(),
+            matched: 0,
+            history: VecDeque::with_capacity(context),
+            context,
+            disassembler,
+            status: Status::Running,
+        }
+    }
+
+    /// Disassembly of the instruction at `address`, preceded by its label if it has one, and its
+    /// length.
+    fn disassemble(&self, address: u16, mmu: &MMU) -> (String, u16) {
+        let line = self.disassembler.disassemble(address, mmu.cartridge.current_rom_bank(), |address| mmu.peek_byte(address));
+        let text = match line.label {
+            Some(label) => format!("{:04X}  {}: {}", address, label, line.text),
+            None => format!("{:04X}  {}", address, line.text),
+        };
+        (text, line.bytes.len() as u16)
+    }
+
+    fn diverge(&mut self, expected: String, actual: String, registers: &Registers, mmu: &MMU) -> Status {
+        let mut next = Vec::new();
+        let mut address = registers.pc;
+        for _ in 0..NEXT_INSTRUCTIONS {
+            let (text, length) = self.disassemble(address, mmu);
+            next.push(text);
+            address = address.wrapping_add(length);
+        }
+        Status::Diverged(Divergence {
+            line: self.matched + 1,
+            expected,
+            actual,
+            history: self.history.drain(..).collect(),
+            next,
+            pc: registers.pc,
+            memory: (0..MEMORY_BYTES).map(|offset| mmu.peek_byte(registers.pc.wrapping_add(offset))).collect(),
+        })
+    }
+}
+
+impl Tracer for Rc<RefCell<Comparison>> {
+    fn trace(&mut self, registers: &Registers, mmu: &MMU) {
+        let mut comparison = self.borrow_mut();
+        if !matches!(comparison.status, Status::Running) {
+            return;
+        }
+        let expected = match comparison.reference.next() {
+            Some(Ok(line)) => line.trim().to_string(),
+            Some(Err(err)) => {
+                comparison.status = Status::Error(format!("Failed to read the reference: {}", err));
+                return;
+            }
+            None => {
+                comparison.status = Status::Matched;
+                return;
+            }
+        };
+        let actual = format_trace_state(registers, mmu);
+        if expected != actual {
+            comparison.status = comparison.diverge(expected, actual, registers, mmu);
+            return;
+        }
+        comparison.matched += 1;
+        if comparison.context > 0 {
+            if comparison.history.len() == comparison.context {
+                comparison.history.pop_front();
+            }
+            let entry = format!("{}  {}", actual, comparison.disassemble(registers.pc, mmu).0);
+            comparison.history.push_back(entry);
+        }
+    }
+}
+
+/// Fields of the trace lines with different values, as `<field>: <expected> != <actual>`.
+fn differences(expected: &str, actual: &str) -> Vec<String> {
+    let fields = |line: &str| -> Vec<(String, String)> {
+        line.split_whitespace()
+            .map(|field| match field.split_once(':') {
+                Some((name, value)) => (name.to_string(), value.to_string()),
+                None => (field.to_string(), String::new()),
+            })
+            .collect()
+    };
+    let actual = fields(actual);
+    fields(expected).into_iter()
+        .filter_map(|(name, expected)| {
+            let value = actual.iter().find(|(field, _)| *field == name).map_or("missing", |(_, value)| value.as_str());
+            (value != expected).then(|| format!("{}: {} != {}", name, expected, value))
+        })
+        .collect()
+}
+
+fn print_divergence(divergence: &Divergence, reference: &Path) {
+    println!("Divergence at line {} of {}", divergence.line, reference.display());
+    println!("  expected  {}", divergence.expected);
+    println!("  actual    {}", divergence.actual);
+    for difference in differences(&divergence.expected, &divergence.actual) {
+        println!("  {}", difference);
+    }
+    if !divergence.history.is_empty() {
+        println!();
+        println!("Previous instructions:");
+        for entry in &divergence.history {
+            println!("  {}", entry);
+        }
+    }
+    println!();
+    println!("Instructions at PC:");
+    for (index, line) in divergence.next.iter().enumerate() {
+        println!("{} {}", if index == 0 { "=>" } else { "  " }, line);
+    }
+    println!();
+    println!("Memory at PC:");
+    print_memory(divergence.pc, MEMORY_BYTES, |address| divergence.memory[address.wrapping_sub(divergence.pc) as usize]);
+}
+
+/// Runs the ROM at `rom_path`, comparing its state before every instruction with the lines of
+/// the trace at `reference`.
+///
+/// Returns whether every line of the reference matched.
+pub(crate) fn run(rom_path: &Path, reference: &Path, options: Options) -> Result<bool, String> {
+    let rom = match Rom::from_path(&rom_path.to_string_lossy()) {
+        Ok(rom) if rom.validate() => rom,
+        Ok(_) => return Err(String::from("Invalid ROM!")),
+        Err(err) => return Err(format!("Failed to load ROM: {}", err)),
+    };
+    let mut mmu = MMU::new().with_rom(rom, RtcClock::Emulated)?;
+    if options.stub_ly {
+        mmu = mmu.with_stubbed_ly();
+    }
+    let file = File::open(reference).map_err(|err| format!("Failed to open {}: {}", reference.display(), err))?;
+    let disassembler = Disassembler::new().with_symbols(options.symbols);
+    let comparison = Rc::new(RefCell::new(Comparison::new(Box::new(BufReader::new(file)), options.context, disassembler)));
+    let mut cpu = CPU::new().with_tracer(Box::new(comparison.clone()));
+    let budget = options.timeout as u64 * CLOCK_SPEED as u64;
+    while matches!(comparison.borrow().status, Status::Running) && mmu.cycles() < budget {
+        cpu.step(&mut mmu);
+    }
+    let mut comparison = comparison.borrow_mut();
+    // The CPU may stop executing instructions right after the last line of the reference.
+    if matches!(comparison.status, Status::Running) && comparison.reference.next().is_none() {
+        comparison.status = Status::Matched;
+    }
+    match &comparison.status {
+        Status::Running => {
+            println!("Timeout after {} matching lines of {}", comparison.matched, reference.display());
+            Ok(false)
+        }
+        Status::Matched => {
+            println!("The {} lines of {} match", comparison.matched, reference.display());
+            Ok(true)
+        }
+        Status::Diverged(divergence) => {
+            print_divergence(divergence, reference);
+            Ok(false)
+        }
+        Status::Error(err) => Err(err.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::cpu::machine;
+
+    /// Stores A at 0xC006, overwriting the `LD B,D` there.
+    const PROGRAM: [u8; 7] = [
+        0x3E, 0x77, // LD A,0x77
+        0xEA, 0x06, 0xC0, // LD (0xC006),A
+        0x00, // NOP
+        0x42, // LD B,D
+    ];
+
+    /// Trace of the first `count` instructions of `PROGRAM`.
+    fn trace(count: usize) -> Vec<String> {
+        let (mut cpu, mut mmu) = machine(&PROGRAM);
+        (0..count).map(|_| {
+            let line = format_trace_state(cpu.registers(), &mmu);
+            cpu.step(&mut mmu);
+            line
+        }).collect()
+    }
+
+    /// Compares `steps` instructions of `PROGRAM` with `reference`.
+    fn compare(reference: &[String], context: usize, steps: usize) -> Comparison {
+        let reference = Cursor::new(reference.join("\n"));
+        let comparison = Rc::new(RefCell::new(Comparison::new(Box::new(reference), context, Disassembler::new())));
+        let (cpu, mut mmu) = machine(&PROGRAM);
+        let mut cpu = cpu.with_tracer(Box::new(comparison.clone()));
+        for _ in 0..steps {
+            cpu.step(&mut mmu);
+        }
+        drop(cpu);
+        Rc::try_unwrap(comparison).ok().unwrap().into_inner()
+    }
+
+    #[test]
+    fn lists_the_fields_that_differ() {
+        assert_eq!(differences("A:01 F:B0 SP:FFFE PC:0100", "A:01 F:80 SP:FFFE PC:0101"),
+                   ["F: B0 != 80", "PC: 0100 != 0101"]);
+        assert_eq!(differences("A:01 LY:90", "A:01"), ["LY: 90 != missing"]);
+        assert!(differences("A:01 F:B0", "A:01 F:B0").is_empty());
+    }
+
+    #[test]
+    fn matches_the_trace_of_the_same_execution() {
+        let comparison = compare(&trace(4), 0, 5);
+        assert!(matches!(comparison.status, Status::Matched));
+        assert_eq!(comparison.matched, 4);
+    }
+
+    #[test]
+    fn reports_the_first_diverging_line_with_the_memory_before_it_ran() {
+        let mut reference = trace(4);
+        reference[1] = reference[1].replace("A:77", "A:00");
+        let comparison = compare(&reference, 1, 4);
+
+        let Status::Diverged(divergence) = &comparison.status else {
+            panic!("The traces did not diverge");
+        };
+        assert_eq!(divergence.line, 2);
+        assert_eq!(divergence.expected, reference[1]);
+        assert!(divergence.actual.contains("A:77"));
+        assert_eq!(differences(&divergence.expected, &divergence.actual), ["A: 00 != 77"]);
+        assert_eq!(divergence.history.len(), 1);
+        assert!(divergence.history[0].starts_with(&reference[0]));
+        assert_eq!(divergence.pc, 0xC002);
+        assert!(divergence.next[0].starts_with("C002"));
+        // The diverging instruction overwrote 0xC006 after the memory was captured.
+        assert_eq!(divergence.memory[..5], PROGRAM[2..]);
+    }
+}
@@ -5,6 +5,7 @@ pub struct Memory {
     pub(crate) data: Vec<u8>,
 }
 
+#[allow(dead_code)]
 pub(crate) trait MemoryTrait {
     fn load(&mut self, path: &str) -> Result<(), std::io::Error>;
 }
@@ -0,0 +1,229 @@
+//! Memory watchpoints, checked by the MMU on every access once at least one is set.
+
+use std::cell::RefCell;
+use std::fmt;
+
+use crate::debugger::parse_number;
+
+/// Memory access reported by a watchpoint.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Access {
+    Read,
+    Write,
+}
+
+/// Accesses a watchpoint triggers on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Trigger {
+    Read,
+    Write,
+    ReadWrite,
+    /// Write of the given value.
+    Value(u8),
+    /// Write changing the value in memory.
+    Change,
+}
+
+/// Watchpoint on an address range, parsed from `<addr>[-<end>]:<r|w|rw|=<value>|change>` with
+/// hexadecimal addresses and value.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct Watchpoint {
+    start: u16,
+    end: u16,
+    trigger: Trigger,
+    /// Whether hits are only logged instead of stopping the debugger.
+    pub(crate) log_only: bool,
+}
+
+impl Watchpoint {
+    pub(crate) fn parse(spec: &str, log_only: bool) -> Result<Self, String> {
+        let (range, trigger) = spec.split_once(':')
+            .ok_or_else(|| format!("Invalid watchpoint {}, expected <addr>[-<end>]:<r|w|rw|=<value>|change>", spec))?;
+        let (start, end) = match range.split_once('-') {
+            Some((start, end)) => (parse_number(start)?, parse_number(end)?),
+            None => (parse_number(range)?, parse_number(range)?),
+        };
+        if end < start {
+            return Err(format!("Invalid watchpoint range {}, the end is before the start", range));
+        }
+        let trigger = match trigger {
+            "r" => Trigger::Read,
+            "w" => Trigger::Write,
+            "rw" => Trigger::ReadWrite,
+            "change" => Trigger::Change,
+            _ => match trigger.strip_prefix('=') {
+                Some(value) => Trigger::Value(u8::from_str_radix(value, 16)
+                    .map_err(|_| format!("Invalid hexadecimal byte: {}", value))?),
+                None => return Err(format!("Unknown watchpoint trigger {}, expected r, w, rw, =<value> or change", trigger)),
+            },
+        };
+        Ok(Watchpoint { start, end, trigger, log_only })
+    }
+
+    fn triggers(&self, access: Access, address: u16, value: u8, previous: u8) -> bool {
+        if address < self.start || address > self.end {
+            return false;
+        }
+        match (self.trigger, access) {
+            (Trigger::Read | Trigger::ReadWrite, Access::Read) => true,
+            (Trigger::Write | Trigger::ReadWrite, Access::Write) => true,
+            (Trigger::Value(expected), Access::Write) => value == expected,
+            (Trigger::Change, Access::Write) => value != previous,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Watchpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "{:04X}:", self.start)?;
+        } else {
+            write!(f, "{:04X}-{:04X}:", self.start, self.end)?;
+        }
+        match self.trigger {
+            Trigger::Read => write!(f, "r")?,
+            Trigger::Write => write!(f, "w")?,
+            Trigger::ReadWrite => write!(f, "rw")?,
+            Trigger::Value(value) => write!(f, "={:02X}", value)?,
+            Trigger::Change => write!(f, "change")?,
+        }
+        if self.log_only {
+            write!(f, " (log)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Access that triggered a watchpoint.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Hit {
+    pub(crate) watchpoint: Watchpoint,
+    access: Access,
+    address: u16,
+    value: u8,
+    /// Value in memory before a write.
+    previous: u8,
+}
+
+impl fmt::Display for Hit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.access {
+            Access::Read => write!(f, "read of {:02X} at {:04X}", self.value, self.address)?,
+            Access::Write => write!(f, "write of {:02X} at {:04X}, was {:02X}", self.value, self.address, self.previous)?,
+        }
+        write!(f, " [watchpoint {}]", self.watchpoint)
+    }
+}
+
+/// Watchpoints set, and the hits recorded since they were last taken.
+///
+/// Hits are recorded from `MMU::read_byte`, which only borrows the MMU, hence the `RefCell`.
+pub(crate) struct Watchpoints {
+    watchpoints: Vec<Watchpoint>,
+    hits: RefCell<Vec<Hit>>,
+}
+
+impl Watchpoints {
+    pub(crate) fn new() -> Self {
+        Watchpoints {
+            watchpoints: Vec::new(),
+            hits: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn list(&self) -> &[Watchpoint] {
+        &self.watchpoints
+    }
+
+    pub(crate) fn add(&mut self, watchpoint: Watchpoint) {
+        self.watchpoints.push(watchpoint);
+    }
+
+    pub(crate) fn remove(&mut self, index: usize) -> Option<Watchpoint> {
+        (index < self.watchpoints.len()).then(|| self.watchpoints.remove(index))
+    }
+
+    pub(crate) fn check(&self, access: Access, address: u16, value: u8, previous: u8) {
+        for watchpoint in &self.watchpoints {
+            if watchpoint.triggers(access, address, value, previous) {
+                self.hits.borrow_mut().push(Hit { watchpoint: *watchpoint, access, address, value, previous });
+            }
+        }
+    }
+
+    pub(crate) fn take_hits(&mut self) -> Vec<Hit> {
+        self.hits.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mmu::MMU;
+
+    fn watchpoint(spec: &str) -> Watchpoint {
+        Watchpoint::parse(spec, false).unwrap()
+    }
+
+    #[test]
+    fn parses_ranges_and_triggers() {
+        assert_eq!(watchpoint("C000-C0FF:rw"),
+                   Watchpoint { start: 0xC000, end: 0xC0FF, trigger: Trigger::ReadWrite, log_only: false });
+        assert_eq!(watchpoint("$FF40:r"),
+                   Watchpoint { start: 0xFF40, end: 0xFF40, trigger: Trigger::Read, log_only: false });
+        assert_eq!(watchpoint("D000:w").trigger, Trigger::Write);
+        assert_eq!(watchpoint("D000:=3C").trigger, Trigger::Value(0x3C));
+        assert_eq!(watchpoint("D000:change").trigger, Trigger::Change);
+        assert!(Watchpoint::parse("D000:w", true).unwrap().log_only);
+    }
+
+    #[test]
+    fn rejects_invalid_watchpoints() {
+        assert!(Watchpoint::parse("C0FF-C000:w", false).unwrap_err().contains("end is before the start"));
+        assert!(Watchpoint::parse("C000", false).is_err());
+        assert!(Watchpoint::parse("C000:x", false).is_err());
+        assert!(Watchpoint::parse("C000:=XY", false).is_err());
+    }
+
+    #[test]
+    fn triggers_on_accesses_in_range_matching_the_trigger() {
+        let read = watchpoint("C000-C001:r");
+        assert!(read.triggers(Access::Read, 0xC001, 0x12, 0x12));
+        assert!(!read.triggers(Access::Write, 0xC000, 0x12, 0x00));
+        assert!(!read.triggers(Access::Read, 0xC002, 0x12, 0x12));
+
+        let write = watchpoint("C000:w");
+        assert!(write.triggers(Access::Write, 0xC000, 0x12, 0x12));
+        assert!(!write.triggers(Access::Read, 0xC000, 0x12, 0x12));
+
+        let read_write = watchpoint("C000:rw");
+        assert!(read_write.triggers(Access::Read, 0xC000, 0x12, 0x12));
+        assert!(read_write.triggers(Access::Write, 0xC000, 0x12, 0x12));
+
+        let value = watchpoint("C000:=42");
+        assert!(value.triggers(Access::Write, 0xC000, 0x42, 0x42));
+        assert!(!value.triggers(Access::Write, 0xC000, 0x43, 0x42));
+        assert!(!value.triggers(Access::Read, 0xC000, 0x42, 0x42));
+
+        let change = watchpoint("C000:change");
+        assert!(change.triggers(Access::Write, 0xC000, 0x43, 0x42));
+        assert!(!change.triggers(Access::Write, 0xC000, 0x42, 0x42));
+        assert!(!change.triggers(Access::Read, 0xC000, 0x42, 0x00));
+    }
+
+    #[test]
+    fn writes_record_hits_but_pokes_do_not() {
+        let mut mmu = MMU::new();
+        mmu.add_watchpoint(watchpoint("C000:w"));
+
+        mmu.poke_byte(0xC000, 0x12);
+        assert!(mmu.take_watchpoint_hits().is_empty());
+
+        mmu.write_byte(0xC000, 0x34);
+        let hits = mmu.take_watchpoint_hits();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].to_string(), "write of 34 at C000, was 12 [watchpoint C000:w]");
+        assert!(mmu.take_watchpoint_hits().is_empty());
+    }
+}
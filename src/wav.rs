@@ -0,0 +1,54 @@
+use std::fs::File;
+use std::io;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Size of the RIFF and format headers preceding the samples.
+const HEADER_SIZE: u32 = 44;
+
+/// Writes signed 16-bit PCM samples to a WAV file.
+///
+/// The sizes in the header are kept up to date after every write, so that the file stays valid
+/// even if the emulator is interrupted.
+pub(crate) struct WavWriter {
+    writer: BufWriter<File>,
+    data_size: u32,
+}
+
+impl WavWriter {
+    pub(crate) fn create(path: &Path, sample_rate: u32, channels: u16) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let block_align = channels * 2;
+        writer.write_all(b"RIFF")?;
+        writer.write_all(&(HEADER_SIZE - 8).to_le_bytes())?;
+        writer.write_all(b"WAVE")?;
+        writer.write_all(b"fmt ")?;
+        writer.write_all(&16u32.to_le_bytes())?;
+        writer.write_all(&1u16.to_le_bytes())?; // PCM
+        writer.write_all(&channels.to_le_bytes())?;
+        writer.write_all(&sample_rate.to_le_bytes())?;
+        writer.write_all(&(sample_rate * block_align as u32).to_le_bytes())?;
+        writer.write_all(&block_align.to_le_bytes())?;
+        writer.write_all(&16u16.to_le_bytes())?; // Bits per sample
+        writer.write_all(b"data")?;
+        writer.write_all(&0u32.to_le_bytes())?;
+        writer.flush()?;
+        Ok(WavWriter { writer, data_size: 0 })
+    }
+
+    pub(crate) fn write_samples(&mut self, samples: &[i16]) -> io::Result<()> {
+        if samples.is_empty() {
+            return Ok(());
+        }
+        for sample in samples {
+            self.writer.write_all(&sample.to_le_bytes())?;
+        }
+        self.data_size += samples.len() as u32 * 2;
+        self.writer.seek(SeekFrom::Start(4))?;
+        self.writer.write_all(&(HEADER_SIZE - 8 + self.data_size).to_le_bytes())?;
+        self.writer.seek(SeekFrom::Start(40))?;
+        self.writer.write_all(&self.data_size.to_le_bytes())?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()
+    }
+}